@@ -1,8 +1,11 @@
+use crate::power::StandbyAxes;
+
 #[derive(Copy, Clone, Debug)]
 pub struct Accel {
     x: i16,
     y: i16,
     z: i16,
+    standby: [bool; 3],
 }
 
 impl Accel {
@@ -14,9 +17,19 @@ impl Accel {
             x: i16::from_be_bytes(x),
             y: i16::from_be_bytes(y),
             z: i16::from_be_bytes(z),
+            standby: [false; 3],
         }
     }
 
+    /// Flag axes in standby, whose registers would only hold stale values.
+    pub(crate) fn with_standby(mut self, standby: &StandbyAxes) -> Self {
+        self.standby = [standby.accel_x, standby.accel_y, standby.accel_z];
+        if self.standby[0] { self.x = 0 }
+        if self.standby[1] { self.y = 0 }
+        if self.standby[2] { self.z = 0 }
+        self
+    }
+
     pub fn x(&self) -> i16 {
         self.x
     }
@@ -28,6 +41,21 @@ impl Accel {
     pub fn z(&self) -> i16 {
         self.z
     }
+
+    /// Whether the X axis was in standby, in which case `x()` reads as zero.
+    pub fn x_standby(&self) -> bool {
+        self.standby[0]
+    }
+
+    /// Whether the Y axis was in standby, in which case `y()` reads as zero.
+    pub fn y_standby(&self) -> bool {
+        self.standby[1]
+    }
+
+    /// Whether the Z axis was in standby, in which case `z()` reads as zero.
+    pub fn z_standby(&self) -> bool {
+        self.standby[2]
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -36,4 +64,40 @@ pub enum AccelFullScale {
     G4 = 1,
     G8 = 2,
     G16 = 3,
-}
\ No newline at end of file
+}
+
+impl AccelFullScale {
+    pub(crate) fn from_byte(byte: u8) -> Self {
+        match byte & 0b11 {
+            0 => AccelFullScale::G2,
+            1 => AccelFullScale::G4,
+            2 => AccelFullScale::G8,
+            _ => AccelFullScale::G16,
+        }
+    }
+}
+
+/// Digital high-pass filter applied to the accelerometer for motion detection.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AccelHighPass {
+    Reset = 0,
+    Hz5 = 1,
+    Hz2_5 = 2,
+    Hz1_25 = 3,
+    Hz0_63 = 4,
+    /// Hold the current sample as the filter output.
+    Hold = 7,
+}
+
+impl AccelHighPass {
+    pub(crate) fn from_byte(byte: u8) -> Self {
+        match byte & 0b111 {
+            1 => AccelHighPass::Hz5,
+            2 => AccelHighPass::Hz2_5,
+            3 => AccelHighPass::Hz1_25,
+            4 => AccelHighPass::Hz0_63,
+            7 => AccelHighPass::Hold,
+            _ => AccelHighPass::Reset,
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use crate::sensor::Mpu6050;
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+use crate::error::Error;
+use crate::aux::{AuxSlave, AuxSlaveConfig, EXT_SENS_DATA_LEN};
+use crate::magnetometer::MagneticField;
+
+const ADDRESS: u8 = 0x0C;
+const WIA: u8 = 0x00;
+const ST1: u8 = 0x02;
+/// ST1, the X, Y and Z measurement, then ST2.
+const DATA_LEN: usize = 8;
+const CNTL: u8 = 0x0A;
+const ASAX: u8 = 0x10;
+const ID: u8 = 0x48;
+const ST1_DRDY: u8 = 1 << 0;
+const ST2_DERR: u8 = 1 << 2;
+const ST2_HOFL: u8 = 1 << 3;
+const MODE_POWER_DOWN: u8 = 0x00;
+const MODE_SINGLE: u8 = 0x01;
+const MODE_FUSE_ROM: u8 = 0x0F;
+/// Sensitivity in microtesla per LSB.
+const SENSITIVITY: f32 = 0.3;
+
+#[derive(Copy, Clone, Debug)]
+pub struct Ak8975Config {
+    /// Slave reading the status and measurement.
+    pub slave: AuxSlave,
+    /// Slave triggering the next single measurement.
+    pub trigger_slave: AuxSlave,
+    /// Access the magnetometer only every `1 + delay` samples.
+    ///
+    /// A measurement takes up to 9 ms, so the resulting rate should stay below 100 Hz.
+    pub sample_delay: u8,
+}
+
+impl Default for Ak8975Config {
+    fn default() -> Self {
+        Self {
+            slave: AuxSlave::Slv0,
+            trigger_slave: AuxSlave::Slv1,
+            sample_delay: 1,
+        }
+    }
+}
+
+/// AsahiKASEI AK8975 magnetometer, as packaged with the MPU-6050 die in the MPU-9150.
+pub struct Ak8975 {
+    config: Ak8975Config,
+    adjustment: [f32; 3],
+    offset: usize,
+}
+
+impl Ak8975 {
+    pub fn new(config: Ak8975Config) -> Self {
+        Self {
+            config,
+            adjustment: [1.0; 3],
+            offset: 0,
+        }
+    }
+
+    /// Whether an AK8975 responds on the auxiliary bus.
+    pub fn detect<I2c, Clock>(sensor: &mut Mpu6050<'_, I2c, Clock>) -> Result<bool, Error<I2c>>
+        where I2c: Write + WriteRead,
+              <I2c as WriteRead>::Error: core::fmt::Debug,
+              <I2c as Write>::Error: core::fmt::Debug,
+              Clock: embedded_time::Clock,
+    {
+        match sensor.aux_read_byte(ADDRESS, WIA) {
+            Ok(id) => Ok(id == ID),
+            Err(Error::AuxNack) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Read the sensitivity adjustment from fuse ROM, then schedule
+    /// periodic single measurements.
+    ///
+    /// The auxiliary master must already be enabled, and any lower-numbered
+    /// slaves configured, as they determine where the data lands in EXT_SENS_DATA;
+    /// `Error::AuxDataOverflow` is returned if the measurement would not fit.
+    pub fn init<I2c, Clock>(&mut self, sensor: &mut Mpu6050<'_, I2c, Clock>) -> Result<(), Error<I2c>>
+        where I2c: Write + WriteRead,
+              <I2c as WriteRead>::Error: core::fmt::Debug,
+              <I2c as Write>::Error: core::fmt::Debug,
+              Clock: embedded_time::Clock,
+    {
+        let offset = sensor.ext_sens_data_offset(self.config.slave)?;
+        if offset + DATA_LEN > EXT_SENS_DATA_LEN {
+            return Err(Error::AuxDataOverflow);
+        }
+
+        if !Self::detect(sensor)? {
+            return Err(Error::WrongDevice);
+        }
+
+        sensor.aux_write_byte(ADDRESS, CNTL, MODE_FUSE_ROM)?;
+        for (i, adjustment) in self.adjustment.iter_mut().enumerate() {
+            let asa = sensor.aux_read_byte(ADDRESS, ASAX + i as u8)?;
+            *adjustment = (asa as f32 - 128.0) / 256.0 + 1.0;
+        }
+        sensor.aux_write_byte(ADDRESS, CNTL, MODE_POWER_DOWN)?;
+
+        self.offset = offset;
+        sensor.set_aux_sample_delay(self.config.sample_delay)?;
+        sensor.configure_aux_slave(self.config.slave, AuxSlaveConfig {
+            delayed: true,
+            ..AuxSlaveConfig::read(ADDRESS, ST1, DATA_LEN as u8)
+        })?;
+        sensor.configure_aux_slave(self.config.trigger_slave, AuxSlaveConfig {
+            delayed: true,
+            ..AuxSlaveConfig::write(ADDRESS, CNTL, MODE_SINGLE)
+        })
+    }
+
+    /// Fuse-ROM sensitivity adjustment factors for X, Y and Z.
+    pub fn sensitivity_adjustment(&self) -> [f32; 3] {
+        self.adjustment
+    }
+
+    /// Latest measurement in the accel and gyro axes of the MPU-9150.
+    ///
+    /// Returns `None` if no new measurement is ready, or it overflowed.
+    pub fn mag<I2c, Clock>(&self, sensor: &mut Mpu6050<'_, I2c, Clock>) -> Result<Option<MagneticField>, Error<I2c>>
+        where I2c: Write + WriteRead,
+              <I2c as WriteRead>::Error: core::fmt::Debug,
+              <I2c as Write>::Error: core::fmt::Debug,
+              Clock: embedded_time::Clock,
+    {
+        let mut buf = [0; EXT_SENS_DATA_LEN];
+        let data = sensor.read_ext_sens_data(&mut buf[0..self.offset + DATA_LEN])?;
+        let data = &data[self.offset..];
+
+        let (st1, st2) = (data[0], data[7]);
+        if (st1 & ST1_DRDY) == 0 || (st2 & (ST2_DERR | ST2_HOFL)) != 0 {
+            return Ok(None);
+        }
+
+        let axis = |i: usize| {
+            let raw = i16::from_le_bytes([data[1 + i * 2], data[2 + i * 2]]);
+            raw as f32 * self.adjustment[i] * SENSITIVITY
+        };
+
+        // the magnetometer's X and Y are swapped, and Z inverted, relative to the accel
+        Ok(Some(MagneticField {
+            x: axis(1),
+            y: axis(0),
+            z: -axis(2),
+        }))
+    }
+}
@@ -0,0 +1,302 @@
+use crate::sensor::Mpu6050;
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+use crate::error::Error;
+use crate::aux::{AuxSlave, AuxSlaveConfig, EXT_SENS_DATA_LEN};
+
+const ADDRESS: u8 = 0x77;
+const CALIBRATION: u8 = 0xAA;
+const CHIP_ID: u8 = 0xD0;
+const CTRL_MEAS: u8 = 0xF4;
+/// ctrl_meas, a reserved byte, then the MSB, LSB and XLSB of the result.
+const DATA_LEN: usize = 5;
+const ID: u8 = 0x55;
+const CTRL_SCO: u8 = 1 << 5;
+const CMD_TEMPERATURE: u8 = 0x2E;
+const CMD_PRESSURE: u8 = 0x34;
+
+/// Standard sea-level pressure in pascal.
+pub const SEA_LEVEL_PRESSURE: f32 = 101_325.0;
+
+/// Number of internal samples per pressure conversion.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Bmp180Oversampling {
+    UltraLowPower = 0,
+    Standard = 1,
+    HighResolution = 2,
+    UltraHighResolution = 3,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Bmp180Config {
+    pub oversampling: Bmp180Oversampling,
+    /// Slave periodically reading the conversion status and result.
+    pub slave: AuxSlave,
+}
+
+impl Default for Bmp180Config {
+    fn default() -> Self {
+        Self {
+            oversampling: Bmp180Oversampling::Standard,
+            slave: AuxSlave::Slv0,
+        }
+    }
+}
+
+/// Compensated barometer measurement.
+#[derive(Copy, Clone, Debug)]
+pub struct Bmp180Measurement {
+    /// Temperature in degrees Celsius.
+    pub temperature: f32,
+    /// Pressure in pascal.
+    pub pressure: i32,
+}
+
+impl Bmp180Measurement {
+    /// Altitude in meters, relative to the given sea-level pressure in pascal.
+    pub fn altitude(&self, sea_level_pressure: f32) -> f32 {
+        let ratio = self.pressure as f32 / sea_level_pressure;
+        44330.0 * (1.0 - libm::powf(ratio, 1.0 / 5.255))
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+struct Calibration {
+    ac1: i16,
+    ac2: i16,
+    ac3: i16,
+    ac4: u16,
+    ac5: u16,
+    ac6: u16,
+    b1: i16,
+    b2: i16,
+    // MB (word 8) is not used by the compensation
+    mc: i16,
+    md: i16,
+}
+
+#[derive(Copy, Clone, Debug)]
+enum State {
+    /// `init()` has not succeeded yet, so there is no calibration to compensate with.
+    Uninitialized,
+    Idle,
+    Temperature,
+    Pressure { ut: i32 },
+}
+
+/// Bosch BMP180 (or register-compatible BMP085) barometer behind the
+/// auxiliary i2c master, as found on GY-87 boards.
+///
+/// Conversions are started through slave 4, while a periodic slave reads
+/// the conversion status and result alongside each IMU sample.
+pub struct Bmp180 {
+    config: Bmp180Config,
+    calibration: Calibration,
+    state: State,
+    offset: usize,
+}
+
+impl Bmp180 {
+    pub fn new(config: Bmp180Config) -> Self {
+        Self {
+            config,
+            calibration: Calibration::default(),
+            state: State::Uninitialized,
+            offset: 0,
+        }
+    }
+
+    /// Read the calibration EEPROM, then schedule periodic reads of the conversion registers.
+    ///
+    /// The auxiliary master must already be enabled, and any lower-numbered
+    /// slaves configured, as they determine where the data lands in EXT_SENS_DATA;
+    /// `Error::AuxDataOverflow` is returned if the conversion registers would not fit.
+    pub fn init<I2c, Clock>(&mut self, sensor: &mut Mpu6050<'_, I2c, Clock>) -> Result<(), Error<I2c>>
+        where I2c: Write + WriteRead,
+              <I2c as WriteRead>::Error: core::fmt::Debug,
+              <I2c as Write>::Error: core::fmt::Debug,
+              Clock: embedded_time::Clock,
+    {
+        self.state = State::Uninitialized;
+        let offset = sensor.ext_sens_data_offset(self.config.slave)?;
+        if offset + DATA_LEN > EXT_SENS_DATA_LEN {
+            return Err(Error::AuxDataOverflow);
+        }
+
+        if sensor.aux_read_byte(ADDRESS, CHIP_ID)? != ID {
+            return Err(Error::WrongDevice);
+        }
+
+        let mut eeprom = [0; 22];
+        for (i, b) in eeprom.iter_mut().enumerate() {
+            *b = sensor.aux_read_byte(ADDRESS, CALIBRATION + i as u8)?;
+        }
+        let word = |i: usize| [eeprom[i * 2], eeprom[i * 2 + 1]];
+        // the datasheet treats 0x0000 and 0xFFFF words as a failed EEPROM read
+        if (0..eeprom.len() / 2).any(|i| matches!(u16::from_be_bytes(word(i)), 0x0000 | 0xFFFF)) {
+            return Err(Error::WrongDevice);
+        }
+        self.calibration = Calibration {
+            ac1: i16::from_be_bytes(word(0)),
+            ac2: i16::from_be_bytes(word(1)),
+            ac3: i16::from_be_bytes(word(2)),
+            ac4: u16::from_be_bytes(word(3)),
+            ac5: u16::from_be_bytes(word(4)),
+            ac6: u16::from_be_bytes(word(5)),
+            b1: i16::from_be_bytes(word(6)),
+            b2: i16::from_be_bytes(word(7)),
+            mc: i16::from_be_bytes(word(9)),
+            md: i16::from_be_bytes(word(10)),
+        };
+
+        self.offset = offset;
+        sensor.configure_aux_slave(self.config.slave, AuxSlaveConfig::read(ADDRESS, CTRL_MEAS, DATA_LEN as u8))?;
+        self.state = State::Idle;
+        Ok(())
+    }
+
+    /// Advance the temperature and pressure conversion cycle.
+    ///
+    /// Call regularly, for instance after each IMU sample. Returns a
+    /// measurement each time a pressure conversion completes, and
+    /// nothing until `init()` has succeeded.
+    pub fn poll<I2c, Clock>(&mut self, sensor: &mut Mpu6050<'_, I2c, Clock>) -> Result<Option<Bmp180Measurement>, Error<I2c>>
+        where I2c: Write + WriteRead,
+              <I2c as WriteRead>::Error: core::fmt::Debug,
+              <I2c as Write>::Error: core::fmt::Debug,
+              Clock: embedded_time::Clock,
+    {
+        match self.state {
+            State::Uninitialized => Ok(None),
+            State::Idle => {
+                sensor.aux_write_byte(ADDRESS, CTRL_MEAS, CMD_TEMPERATURE)?;
+                self.state = State::Temperature;
+                Ok(None)
+            }
+            State::Temperature => {
+                if let Some(data) = self.completed(sensor, CMD_TEMPERATURE)? {
+                    let ut = ((data[0] as i32) << 8) | data[1] as i32;
+                    sensor.aux_write_byte(ADDRESS, CTRL_MEAS, self.pressure_command())?;
+                    self.state = State::Pressure { ut };
+                }
+                Ok(None)
+            }
+            State::Pressure { ut } => {
+                match self.completed(sensor, self.pressure_command())? {
+                    Some(data) => {
+                        let oss = self.config.oversampling as u32;
+                        let up = (((data[0] as i32) << 16) | ((data[1] as i32) << 8) | data[2] as i32) >> (8 - oss);
+                        self.state = State::Idle;
+                        Ok(Some(self.compensate(ut, up)))
+                    }
+                    None => Ok(None),
+                }
+            }
+        }
+    }
+
+    fn pressure_command(&self) -> u8 {
+        CMD_PRESSURE | ((self.config.oversampling as u8) << 6)
+    }
+
+    /// Result bytes, once the conversion started by `command` has completed.
+    fn completed<I2c, Clock>(&self, sensor: &mut Mpu6050<'_, I2c, Clock>, command: u8) -> Result<Option<[u8; 3]>, Error<I2c>>
+        where I2c: Write + WriteRead,
+              <I2c as WriteRead>::Error: core::fmt::Debug,
+              <I2c as Write>::Error: core::fmt::Debug,
+              Clock: embedded_time::Clock,
+    {
+        let mut buf = [0; EXT_SENS_DATA_LEN];
+        let data = sensor.read_ext_sens_data(&mut buf[0..self.offset + DATA_LEN])?;
+        let data = &data[self.offset..];
+        if !conversion_done(data[0], command) {
+            return Ok(None);
+        }
+        Ok(Some([data[2], data[3], data[4]]))
+    }
+
+    /// Datasheet compensation of the raw temperature and pressure.
+    fn compensate(&self, ut: i32, up: i32) -> Bmp180Measurement {
+        let c = &self.calibration;
+        let oss = self.config.oversampling as u32;
+
+        let x1 = ((ut - c.ac6 as i32) * c.ac5 as i32) >> 15;
+        let x2 = ((c.mc as i32) << 11) / (x1 + c.md as i32);
+        let b5 = x1 + x2;
+        let temperature = ((b5 + 8) >> 4) as f32 / 10.0;
+
+        let b6 = b5 - 4000;
+        let x1 = (c.b2 as i32 * ((b6 * b6) >> 12)) >> 11;
+        let x2 = (c.ac2 as i32 * b6) >> 11;
+        let x3 = x1 + x2;
+        let b3 = ((((c.ac1 as i32) * 4 + x3) << oss) + 2) / 4;
+        let x1 = (c.ac3 as i32 * b6) >> 13;
+        let x2 = (c.b1 as i32 * ((b6 * b6) >> 12)) >> 16;
+        let x3 = (x1 + x2 + 2) >> 2;
+        let b4 = (c.ac4 as u32 * (x3 + 32768) as u32) >> 15;
+        let b7 = (up - b3) as u32 * (50000 >> oss);
+        let p = if b7 < 0x8000_0000 {
+            (b7 * 2) / b4
+        } else {
+            (b7 / b4) * 2
+        } as i32;
+        let x1 = ((p >> 8) * (p >> 8) * 3038) >> 16;
+        let x2 = (-7357 * p) >> 16;
+        let pressure = p + ((x1 + x2 + 3791) >> 4);
+
+        Bmp180Measurement {
+            temperature,
+            pressure,
+        }
+    }
+}
+
+/// Whether a sampled ctrl_meas value shows the conversion started by `command` as complete.
+///
+/// The commands set SCO, which the chip clears once the conversion is done;
+/// the sampled register may also still predate the command.
+fn conversion_done(ctrl_meas: u8, command: u8) -> bool {
+    (ctrl_meas & !CTRL_SCO) == (command & !CTRL_SCO) && (ctrl_meas & CTRL_SCO) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversion_done_once_sco_clears() {
+        let pressure = CMD_PRESSURE | ((Bmp180Oversampling::HighResolution as u8) << 6);
+
+        assert!(!conversion_done(CMD_TEMPERATURE, CMD_TEMPERATURE));
+        assert!(conversion_done(CMD_TEMPERATURE & !CTRL_SCO, CMD_TEMPERATURE));
+        assert!(!conversion_done(pressure, pressure));
+        assert!(conversion_done(pressure & !CTRL_SCO, pressure));
+
+        // a finished conversion of the other kind is stale data
+        assert!(!conversion_done(CMD_TEMPERATURE & !CTRL_SCO, pressure));
+        assert!(!conversion_done(pressure & !CTRL_SCO, CMD_TEMPERATURE));
+    }
+
+    #[test]
+    fn compensate_datasheet_example() {
+        let mut bmp = Bmp180::new(Bmp180Config {
+            oversampling: Bmp180Oversampling::UltraLowPower,
+            ..Default::default()
+        });
+        bmp.calibration = Calibration {
+            ac1: 408,
+            ac2: -72,
+            ac3: -14383,
+            ac4: 32741,
+            ac5: 32757,
+            ac6: 23153,
+            b1: 6190,
+            b2: 4,
+            mc: -8711,
+            md: 2868,
+        };
+
+        let m = bmp.compensate(27898, 23843);
+        assert!((m.temperature - 15.0).abs() < 1e-6);
+        assert_eq!(m.pressure, 69964);
+    }
+}
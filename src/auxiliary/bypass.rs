@@ -0,0 +1,102 @@
+use crate::sensor::Mpu6050;
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+use crate::error::Error;
+use drogue_embedded_timer::Delay;
+use embedded_time::duration::Milliseconds;
+use crate::registers::Register;
+use crate::aux::{INT_PIN_I2C_BYPASS_EN, USER_CTRL_I2C_MST_EN};
+
+impl<'clock, I2c, Clock> Mpu6050<'clock, I2c, Clock>
+    where I2c: Write + WriteRead,
+          <I2c as WriteRead>::Error: core::fmt::Debug,
+          <I2c as Write>::Error: core::fmt::Debug,
+          Clock: embedded_time::Clock {
+
+    /// Connect the auxiliary bus directly to the host bus.
+    ///
+    /// The auxiliary i2c master is disabled first, as it would otherwise drive the bus.
+    pub fn enable_bypass(&mut self) -> Result<(), Error<I2c>> {
+        self.disable_aux_master()?;
+        // let any slave transaction in progress finish before bridging the buses
+        Delay::new(self.clock).delay(Milliseconds(3u32));
+
+        let mut value = self.read_register(Register::IntPinCfg)?;
+        value |= INT_PIN_I2C_BYPASS_EN;
+        self.write_register(Register::IntPinCfg, value)
+    }
+
+    /// Disconnect the auxiliary bus from the host bus.
+    /// The auxiliary i2c master is left disabled.
+    pub fn disable_bypass(&mut self) -> Result<(), Error<I2c>> {
+        let mut value = self.read_register(Register::IntPinCfg)?;
+        value &= !INT_PIN_I2C_BYPASS_EN;
+        self.write_register(Register::IntPinCfg, value)
+    }
+
+    /// Enable bypass mode, returning an i2c handle for sensors on the auxiliary bus.
+    ///
+    /// Bypass mode is disabled again, and the auxiliary master restored,
+    /// when the handle is dropped.
+    pub fn bypass(&mut self) -> Result<AuxBypass<'_, 'clock, I2c, Clock>, Error<I2c>> {
+        let master = (self.read_register(Register::UserCtrl)? & USER_CTRL_I2C_MST_EN) != 0;
+        self.enable_bypass()?;
+        Ok(AuxBypass {
+            sensor: self,
+            master,
+        })
+    }
+}
+
+/// i2c handle onto the host bus, with the MPU's auxiliary bus bridged onto it.
+///
+/// Implements embedded-hal `Write` and `WriteRead`, so existing drivers for
+/// external sensors can be used unchanged.
+pub struct AuxBypass<'a, 'clock, I2c, Clock>
+    where I2c: Write + WriteRead,
+          <I2c as WriteRead>::Error: core::fmt::Debug,
+          <I2c as Write>::Error: core::fmt::Debug,
+          Clock: embedded_time::Clock,
+{
+    sensor: &'a mut Mpu6050<'clock, I2c, Clock>,
+    master: bool,
+}
+
+impl<'a, 'clock, I2c, Clock> Write for AuxBypass<'a, 'clock, I2c, Clock>
+    where I2c: Write + WriteRead,
+          <I2c as WriteRead>::Error: core::fmt::Debug,
+          <I2c as Write>::Error: core::fmt::Debug,
+          Clock: embedded_time::Clock,
+{
+    type Error = <I2c as Write>::Error;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.sensor.i2c.write(address, bytes)
+    }
+}
+
+impl<'a, 'clock, I2c, Clock> WriteRead for AuxBypass<'a, 'clock, I2c, Clock>
+    where I2c: Write + WriteRead,
+          <I2c as WriteRead>::Error: core::fmt::Debug,
+          <I2c as Write>::Error: core::fmt::Debug,
+          Clock: embedded_time::Clock,
+{
+    type Error = <I2c as WriteRead>::Error;
+
+    fn write_read(&mut self, address: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.sensor.i2c.write_read(address, bytes, buffer)
+    }
+}
+
+impl<'a, 'clock, I2c, Clock> Drop for AuxBypass<'a, 'clock, I2c, Clock>
+    where I2c: Write + WriteRead,
+          <I2c as WriteRead>::Error: core::fmt::Debug,
+          <I2c as Write>::Error: core::fmt::Debug,
+          Clock: embedded_time::Clock,
+{
+    fn drop(&mut self) {
+        // errors cannot be reported from drop; disable_bypass() may be retried explicitly
+        if self.sensor.disable_bypass().is_ok() && self.master {
+            let _ = self.sensor.resume_aux_master();
+        }
+    }
+}
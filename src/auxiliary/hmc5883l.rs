@@ -0,0 +1,162 @@
+use crate::sensor::Mpu6050;
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+use crate::error::Error;
+use crate::aux::{AuxSlave, AuxSlaveConfig, EXT_SENS_DATA_LEN};
+use crate::magnetometer::MagneticField;
+
+const ADDRESS: u8 = 0x1E;
+const CONFIG_A: u8 = 0x00;
+const CONFIG_B: u8 = 0x01;
+const MODE: u8 = 0x02;
+const DATA_X_H: u8 = 0x03;
+/// X, Z and Y, two bytes each.
+const DATA_LEN: usize = 6;
+const ID_A: u8 = 0x0A;
+const ID: [u8; 3] = *b"H43";
+const MODE_CONTINUOUS: u8 = 0x00;
+/// Reported for an axis whose measurement overflowed.
+const OVERFLOW: i16 = -4096;
+
+/// Measurement range and resolution.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Hmc5883lGain {
+    Gauss0_88 = 0,
+    Gauss1_3 = 1,
+    Gauss1_9 = 2,
+    Gauss2_5 = 3,
+    Gauss4_0 = 4,
+    Gauss4_7 = 5,
+    Gauss5_6 = 6,
+    Gauss8_1 = 7,
+}
+
+impl Hmc5883lGain {
+    fn lsb_per_gauss(self) -> f32 {
+        match self {
+            Hmc5883lGain::Gauss0_88 => 1370.0,
+            Hmc5883lGain::Gauss1_3 => 1090.0,
+            Hmc5883lGain::Gauss1_9 => 820.0,
+            Hmc5883lGain::Gauss2_5 => 660.0,
+            Hmc5883lGain::Gauss4_0 => 440.0,
+            Hmc5883lGain::Gauss4_7 => 390.0,
+            Hmc5883lGain::Gauss5_6 => 330.0,
+            Hmc5883lGain::Gauss8_1 => 230.0,
+        }
+    }
+}
+
+/// Continuous measurement output rate.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Hmc5883lRate {
+    Hz0_75 = 0,
+    Hz1_5 = 1,
+    Hz3 = 2,
+    Hz7_5 = 3,
+    Hz15 = 4,
+    Hz30 = 5,
+    Hz75 = 6,
+}
+
+/// Number of samples averaged per measurement.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Hmc5883lAveraging {
+    Samples1 = 0,
+    Samples2 = 1,
+    Samples4 = 2,
+    Samples8 = 3,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct Hmc5883lConfig {
+    pub gain: Hmc5883lGain,
+    pub rate: Hmc5883lRate,
+    pub averaging: Hmc5883lAveraging,
+    /// Slave used for the periodic reads of the measurement.
+    pub slave: AuxSlave,
+}
+
+impl Default for Hmc5883lConfig {
+    fn default() -> Self {
+        Self {
+            gain: Hmc5883lGain::Gauss1_3,
+            rate: Hmc5883lRate::Hz75,
+            averaging: Hmc5883lAveraging::Samples8,
+            slave: AuxSlave::Slv0,
+        }
+    }
+}
+
+/// Honeywell HMC5883L magnetometer behind the auxiliary i2c master,
+/// as found on GY-86 and GY-87 boards.
+pub struct Hmc5883l {
+    config: Hmc5883lConfig,
+    offset: usize,
+}
+
+impl Hmc5883l {
+    pub fn new(config: Hmc5883lConfig) -> Self {
+        Self {
+            config,
+            offset: 0,
+        }
+    }
+
+    /// Verify and configure the chip, then schedule periodic reads of its measurement.
+    ///
+    /// The auxiliary master must already be enabled, and any lower-numbered
+    /// slaves configured, as they determine where the data lands in EXT_SENS_DATA;
+    /// `Error::AuxDataOverflow` is returned if the measurement would not fit.
+    pub fn init<I2c, Clock>(&mut self, sensor: &mut Mpu6050<'_, I2c, Clock>) -> Result<(), Error<I2c>>
+        where I2c: Write + WriteRead,
+              <I2c as WriteRead>::Error: core::fmt::Debug,
+              <I2c as Write>::Error: core::fmt::Debug,
+              Clock: embedded_time::Clock,
+    {
+        let offset = sensor.ext_sens_data_offset(self.config.slave)?;
+        if offset + DATA_LEN > EXT_SENS_DATA_LEN {
+            return Err(Error::AuxDataOverflow);
+        }
+
+        for (i, expected) in ID.iter().enumerate() {
+            if sensor.aux_read_byte(ADDRESS, ID_A + i as u8)? != *expected {
+                return Err(Error::WrongDevice);
+            }
+        }
+
+        let config_a = ((self.config.averaging as u8) << 5) | ((self.config.rate as u8) << 2);
+        sensor.aux_write_byte(ADDRESS, CONFIG_A, config_a)?;
+        sensor.aux_write_byte(ADDRESS, CONFIG_B, (self.config.gain as u8) << 5)?;
+        sensor.aux_write_byte(ADDRESS, MODE, MODE_CONTINUOUS)?;
+
+        self.offset = offset;
+        sensor.configure_aux_slave(self.config.slave, AuxSlaveConfig::read(ADDRESS, DATA_X_H, DATA_LEN as u8))
+    }
+
+    /// Latest measurement, or `None` if any axis overflowed the configured gain.
+    pub fn read<I2c, Clock>(&self, sensor: &mut Mpu6050<'_, I2c, Clock>) -> Result<Option<MagneticField>, Error<I2c>>
+        where I2c: Write + WriteRead,
+              <I2c as WriteRead>::Error: core::fmt::Debug,
+              <I2c as Write>::Error: core::fmt::Debug,
+              Clock: embedded_time::Clock,
+    {
+        let mut buf = [0; EXT_SENS_DATA_LEN];
+        let data = sensor.read_ext_sens_data(&mut buf[0..self.offset + DATA_LEN])?;
+        let data = &data[self.offset..];
+
+        // the chip outputs X, Z, Y
+        let x = i16::from_be_bytes([data[0], data[1]]);
+        let z = i16::from_be_bytes([data[2], data[3]]);
+        let y = i16::from_be_bytes([data[4], data[5]]);
+        if x == OVERFLOW || y == OVERFLOW || z == OVERFLOW {
+            return Ok(None);
+        }
+
+        // 1 gauss is 100 microtesla
+        let scale = 100.0 / self.config.gain.lsb_per_gauss();
+        Ok(Some(MagneticField {
+            x: x as f32 * scale,
+            y: y as f32 * scale,
+            z: z as f32 * scale,
+        }))
+    }
+}
@@ -0,0 +1,172 @@
+use crate::sensor::Mpu6050;
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+use crate::error::Error;
+use drogue_embedded_timer::Delay;
+use embedded_time::duration::Milliseconds;
+use crate::registers::Register;
+use crate::aux::{AuxMasterConfig, AuxSlave, AuxSlaveConfig, AUX_READ, AUX_SLV_EN, EXT_SENS_DATA_LEN, INT_PIN_I2C_BYPASS_EN, MST_CTRL_SLV_3_FIFO_EN, USER_CTRL_I2C_MST_EN};
+
+const MST_CTRL_MULT_MST_EN: u8 = 1 << 7;
+const MST_CTRL_WAIT_FOR_ES: u8 = 1 << 6;
+const MST_CTRL_P_NSR: u8 = 1 << 4;
+const DELAY_ES_SHADOW: u8 = 1 << 7;
+const SLV4_MST_DLY_MASK: u8 = 0b0001_1111;
+const MST_STATUS_SLV4_DONE: u8 = 1 << 6;
+const MST_STATUS_LOST_ARB: u8 = 1 << 5;
+const MST_STATUS_SLV4_NACK: u8 = 1 << 4;
+
+/// How long to wait for a slave 4 transfer, which happens at the sample rate.
+const SLV4_TIMEOUT_MS: u32 = 50;
+
+impl<'clock, I2c, Clock> Mpu6050<'clock, I2c, Clock>
+    where I2c: Write + WriteRead,
+          <I2c as WriteRead>::Error: core::fmt::Debug,
+          <I2c as Write>::Error: core::fmt::Debug,
+          Clock: embedded_time::Clock {
+
+    /// Enable the auxiliary i2c master, taking over the auxiliary bus from bypass mode.
+    pub fn enable_aux_master(&mut self, config: AuxMasterConfig) -> Result<(), Error<I2c>> {
+        let mut value = self.read_register(Register::I2cMstCtrl)?;
+        value &= MST_CTRL_MULT_MST_EN;
+        value |= config.clock as u8;
+        if config.wait_for_external_sensors { value |= MST_CTRL_WAIT_FOR_ES }
+        if config.slave3_fifo { value |= MST_CTRL_SLV_3_FIFO_EN }
+        if config.stop_between_reads { value |= MST_CTRL_P_NSR }
+        self.write_register(Register::I2cMstCtrl, value)?;
+
+        let mut value = self.read_register(Register::I2cMstDelayCtrl)?;
+        if config.delay_shadow {
+            value |= DELAY_ES_SHADOW;
+        } else {
+            value &= !DELAY_ES_SHADOW;
+        }
+        self.write_register(Register::I2cMstDelayCtrl, value)?;
+
+        let mut value = self.read_register(Register::IntPinCfg)?;
+        value &= !INT_PIN_I2C_BYPASS_EN;
+        self.write_register(Register::IntPinCfg, value)?;
+
+        self.resume_aux_master()
+    }
+
+    /// Set the master enable bit, keeping the existing master configuration.
+    pub(crate) fn resume_aux_master(&mut self) -> Result<(), Error<I2c>> {
+        let mut value = self.read_register(Register::UserCtrl)?;
+        value |= USER_CTRL_I2C_MST_EN;
+        self.write_register(Register::UserCtrl, value)
+    }
+
+    pub fn disable_aux_master(&mut self) -> Result<(), Error<I2c>> {
+        let mut value = self.read_register(Register::UserCtrl)?;
+        value &= !USER_CTRL_I2C_MST_EN;
+        self.write_register(Register::UserCtrl, value)
+    }
+
+    /// Configure a slave to transfer on every (possibly delayed) sample.
+    pub fn configure_aux_slave(&mut self, slave: AuxSlave, config: AuxSlaveConfig) -> Result<(), Error<I2c>> {
+        let (addr, reg, ctrl, data_out) = slave.registers();
+        let mut address = config.address & 0x7F;
+        match config.write {
+            Some(value) => self.write_register(data_out, value)?,
+            None => address |= AUX_READ,
+        }
+        self.write_register(addr, address)?;
+        self.write_register(reg, config.register)?;
+        self.write_register(ctrl, config.ctrl_byte())?;
+
+        let mut value = self.read_register(Register::I2cMstDelayCtrl)?;
+        if config.delayed {
+            value |= 1 << slave as u8;
+        } else {
+            value &= !(1 << slave as u8);
+        }
+        self.write_register(Register::I2cMstDelayCtrl, value)
+    }
+
+    pub fn disable_aux_slave(&mut self, slave: AuxSlave) -> Result<(), Error<I2c>> {
+        let (_, _, ctrl, _) = slave.registers();
+        let mut value = self.read_register(ctrl)?;
+        value &= !AUX_SLV_EN;
+        self.write_register(ctrl, value)
+    }
+
+    /// Number of bytes read by a slave, or zero if it is disabled or writing.
+    pub fn aux_slave_len(&mut self, slave: AuxSlave) -> Result<usize, Error<I2c>> {
+        let (addr, _, ctrl, _) = slave.registers();
+        let value = self.read_register(ctrl)?;
+        if (value & AUX_SLV_EN) == 0 || (self.read_register(addr)? & AUX_READ) == 0 {
+            return Ok(0);
+        }
+        Ok((value & 0x0F) as usize)
+    }
+
+    /// Position in EXT_SENS_DATA of the data read by `slave`,
+    /// following the data of all lower-numbered reading slaves.
+    pub(crate) fn ext_sens_data_offset(&mut self, slave: AuxSlave) -> Result<usize, Error<I2c>> {
+        let mut offset = 0;
+        for lower in [AuxSlave::Slv0, AuxSlave::Slv1, AuxSlave::Slv2].iter() {
+            if (*lower as u8) < (slave as u8) {
+                offset += self.aux_slave_len(*lower)?;
+            }
+        }
+        Ok(offset)
+    }
+
+    /// Access delayed slaves only every `1 + delay` samples, with `delay` up to 31.
+    pub fn set_aux_sample_delay(&mut self, delay: u8) -> Result<(), Error<I2c>> {
+        let mut value = self.read_register(Register::I2cSlv4Ctrl)?;
+        value &= !SLV4_MST_DLY_MASK;
+        value |= delay.min(SLV4_MST_DLY_MASK);
+        self.write_register(Register::I2cSlv4Ctrl, value)
+    }
+
+    /// Read the data last received from the slaves, starting at EXT_SENS_DATA_00.
+    ///
+    /// Reading slaves fill these registers in slave order, each taking its `len` bytes.
+    pub fn read_ext_sens_data<'a>(&mut self, buf: &'a mut [u8]) -> Result<&'a [u8], Error<I2c>> {
+        let len = buf.len().min(EXT_SENS_DATA_LEN);
+        self.read_registers(Register::ExtSensData00, &mut buf[0..len])
+    }
+
+    /// Read a single register of an external sensor through slave 4.
+    ///
+    /// Blocks until the transfer completes. Requires the auxiliary master to be enabled.
+    pub fn aux_read_byte(&mut self, address: u8, register: u8) -> Result<u8, Error<I2c>> {
+        self.write_register(Register::I2cSlv4Addr, (address & 0x7F) | AUX_READ)?;
+        self.write_register(Register::I2cSlv4Reg, register)?;
+        self.start_slv4_transfer()?;
+        self.read_register(Register::I2cSlv4Di)
+    }
+
+    /// Write a single register of an external sensor through slave 4.
+    ///
+    /// Blocks until the transfer completes. Requires the auxiliary master to be enabled.
+    pub fn aux_write_byte(&mut self, address: u8, register: u8, value: u8) -> Result<(), Error<I2c>> {
+        self.write_register(Register::I2cSlv4Addr, address & 0x7F)?;
+        self.write_register(Register::I2cSlv4Reg, register)?;
+        self.write_register(Register::I2cSlv4Do, value)?;
+        self.start_slv4_transfer()
+    }
+
+    fn start_slv4_transfer(&mut self) -> Result<(), Error<I2c>> {
+        let mut value = self.read_register(Register::I2cSlv4Ctrl)?;
+        value &= SLV4_MST_DLY_MASK;
+        value |= AUX_SLV_EN;
+        self.write_register(Register::I2cSlv4Ctrl, value)?;
+
+        for _ in 0..SLV4_TIMEOUT_MS {
+            let status = self.read_register(Register::I2cMstStatus)?;
+            if (status & MST_STATUS_SLV4_NACK) != 0 {
+                return Err(Error::AuxNack);
+            }
+            if (status & MST_STATUS_LOST_ARB) != 0 {
+                return Err(Error::AuxArbitrationLost);
+            }
+            if (status & MST_STATUS_SLV4_DONE) != 0 {
+                return Ok(());
+            }
+            Delay::new(self.clock).delay(Milliseconds(1u32));
+        }
+        Err(Error::AuxTimeout)
+    }
+}
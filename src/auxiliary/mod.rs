@@ -0,0 +1,143 @@
+//! Auxiliary i2c master, for external sensors attached to AUX_DA and AUX_CL.
+
+use crate::registers::Register;
+
+mod master;
+mod bypass;
+#[cfg(feature = "hmc5883l")]
+mod hmc5883l;
+mod ak8975;
+mod bmp180;
+
+pub use bypass::AuxBypass;
+#[cfg(feature = "hmc5883l")]
+pub use hmc5883l::{Hmc5883l, Hmc5883lAveraging, Hmc5883lConfig, Hmc5883lGain, Hmc5883lRate};
+pub use ak8975::{Ak8975, Ak8975Config};
+pub use bmp180::{Bmp180, Bmp180Config, Bmp180Measurement, Bmp180Oversampling, SEA_LEVEL_PRESSURE};
+
+/// Number of EXT_SENS_DATA registers shared by all slaves.
+pub const EXT_SENS_DATA_LEN: usize = 24;
+
+/// Slaves which the auxiliary master accesses on every sample.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AuxSlave {
+    Slv0 = 0,
+    Slv1 = 1,
+    Slv2 = 2,
+    Slv3 = 3,
+}
+
+impl AuxSlave {
+    /// ADDR, REG, CTRL and DO registers of the slave.
+    pub(crate) fn registers(self) -> (Register, Register, Register, Register) {
+        match self {
+            AuxSlave::Slv0 => (Register::I2cSlv0Addr, Register::I2cSlv0Reg, Register::I2cSlv0Ctrl, Register::I2cSlv0Do),
+            AuxSlave::Slv1 => (Register::I2cSlv1Addr, Register::I2cSlv1Reg, Register::I2cSlv1Ctrl, Register::I2cSlv1Do),
+            AuxSlave::Slv2 => (Register::I2cSlv2Addr, Register::I2cSlv2Reg, Register::I2cSlv2Ctrl, Register::I2cSlv2Do),
+            AuxSlave::Slv3 => (Register::I2cSlv3Addr, Register::I2cSlv3Reg, Register::I2cSlv3Ctrl, Register::I2cSlv3Do),
+        }
+    }
+}
+
+/// Auxiliary i2c master clock (I2C_MST_CLK).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AuxMasterClock {
+    Khz348 = 0,
+    Khz333 = 1,
+    Khz320 = 2,
+    Khz308 = 3,
+    Khz296 = 4,
+    Khz286 = 5,
+    Khz276 = 6,
+    Khz267 = 7,
+    Khz258 = 8,
+    Khz500 = 9,
+    Khz471 = 10,
+    Khz444 = 11,
+    Khz421 = 12,
+    Khz400 = 13,
+    Khz381 = 14,
+    Khz364 = 15,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct AuxMasterConfig {
+    pub clock: AuxMasterClock,
+    /// Delay the data-ready interrupt until external sensor data is loaded.
+    pub wait_for_external_sensors: bool,
+    /// Issue a stop, rather than a restart, between slave reads.
+    pub stop_between_reads: bool,
+    /// Push slave 3 data into the FIFO. Slaves 0 to 2 are enabled through `Fifo`.
+    pub slave3_fifo: bool,
+    /// Only update EXT_SENS_DATA once data from all slaves has been received.
+    pub delay_shadow: bool,
+}
+
+impl Default for AuxMasterConfig {
+    fn default() -> Self {
+        Self {
+            clock: AuxMasterClock::Khz400,
+            wait_for_external_sensors: true,
+            stop_between_reads: false,
+            slave3_fifo: false,
+            delay_shadow: true,
+        }
+    }
+}
+
+/// Periodic transfer performed by one of the auxiliary slaves.
+#[derive(Copy, Clone, Debug)]
+pub struct AuxSlaveConfig {
+    /// 7-bit i2c address of the external sensor.
+    pub address: u8,
+    pub register: u8,
+    /// Number of bytes to read, up to 15. Writes always transfer one byte.
+    pub len: u8,
+    /// `Some(value)` writes `value` on each sample rather than reading.
+    pub write: Option<u8>,
+    /// Swap the bytes of each word read.
+    pub byte_swap: bool,
+    /// Pair bytes into words starting at odd, rather than even, register addresses.
+    pub group_odd: bool,
+    /// Transfer data without first writing the register address.
+    pub register_disabled: bool,
+    /// Only access the slave every `1 + delay` samples, see `set_aux_sample_delay()`.
+    pub delayed: bool,
+}
+
+impl AuxSlaveConfig {
+    pub fn read(address: u8, register: u8, len: u8) -> Self {
+        Self {
+            address,
+            register,
+            len,
+            write: None,
+            byte_swap: false,
+            group_odd: false,
+            register_disabled: false,
+            delayed: false,
+        }
+    }
+
+    pub fn write(address: u8, register: u8, value: u8) -> Self {
+        Self {
+            len: 1,
+            write: Some(value),
+            ..Self::read(address, register, 1)
+        }
+    }
+
+    pub(crate) fn ctrl_byte(&self) -> u8 {
+        let mut byte = AUX_SLV_EN | (self.len.min(15));
+        if self.byte_swap         { byte |= 1 << 6 }
+        if self.register_disabled { byte |= 1 << 5 }
+        if self.group_odd         { byte |= 1 << 4 }
+        byte
+    }
+}
+
+pub(crate) const USER_CTRL_I2C_MST_EN: u8 = 1 << 5;
+pub(crate) const INT_PIN_I2C_BYPASS_EN: u8 = 1 << 1;
+pub(crate) const AUX_SLV_EN: u8 = 1 << 7;
+pub(crate) const AUX_READ: u8 = 1 << 7;
+pub(crate) const MST_CTRL_SLV_3_FIFO_EN: u8 = 1 << 5;
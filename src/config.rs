@@ -9,4 +9,39 @@ pub enum DigitalLowPassFilter {
     Filter4 = 4,
     Filter5 = 5,
     Filter6 = 6,
-}
\ No newline at end of file
+}
+
+/// Sensor output whose LSB latches the FSYNC pin state (EXT_SYNC_SET).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FrameSyncSource {
+    Disabled = 0,
+    Temp = 1,
+    GyroX = 2,
+    GyroY = 3,
+    GyroZ = 4,
+    AccelX = 5,
+    AccelY = 6,
+    AccelZ = 7,
+}
+
+impl FrameSyncSource {
+    pub(crate) fn from_byte(byte: u8) -> Self {
+        match byte & 0b111 {
+            1 => FrameSyncSource::Temp,
+            2 => FrameSyncSource::GyroX,
+            3 => FrameSyncSource::GyroY,
+            4 => FrameSyncSource::GyroZ,
+            5 => FrameSyncSource::AccelX,
+            6 => FrameSyncSource::AccelY,
+            7 => FrameSyncSource::AccelZ,
+            _ => FrameSyncSource::Disabled,
+        }
+    }
+}
+
+/// Logic level of the FSYNC pin which raises the FSYNC interrupt.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FsyncLevel {
+    ActiveHigh,
+    ActiveLow,
+}
@@ -0,0 +1,225 @@
+use crate::sensor::Mpu6050;
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+use crate::error::Error;
+use crate::accel::AccelFullScale;
+use crate::gyro::GyroBiases;
+use crate::dmp_packet::DmpOutputs;
+use crate::dmp_keys::*;
+use crate::tap::{TapAxes, TapConfig};
+use embedded_time::duration::Milliseconds;
+
+impl<'clock, I2c, Clock> Mpu6050<'clock, I2c, Clock>
+    where I2c: Write + WriteRead,
+          <I2c as WriteRead>::Error: core::fmt::Debug,
+          <I2c as Write>::Error: core::fmt::Debug,
+          Clock: embedded_time::Clock {
+
+    /// Select the sensor data the DMP pushes into the FIFO.
+    /// The FIFO is reset, and `dmp_packet_layout()` updated accordingly.
+    pub fn set_dmp_outputs(&mut self, outputs: DmpOutputs) -> Result<(), Error<I2c>> {
+        let mut cfg = [DINAA3; 10];
+        if outputs.accel {
+            cfg[1..4].copy_from_slice(&[0xC0, 0xC8, 0xC2]);
+        }
+        if outputs.gyro {
+            cfg[4..7].copy_from_slice(&[0xC4, 0xCC, 0xC6]);
+        }
+        self.write_memory(CFG_15, &cfg)?;
+
+        if outputs.gyro {
+            let source = if outputs.calibrated_gyro {
+                [0xB2, 0x8B, 0xB6, 0x9B]
+            } else {
+                [0xB0, 0x80, 0xB4, 0x90]
+            };
+            self.write_memory(CFG_GYRO_RAW_DATA, &source)?;
+        }
+
+        let quaternion_3x = outputs.quaternion_3x && !outputs.quaternion_6x;
+        let lp_quat = if quaternion_3x { [0xC0, 0xC2, 0xC4, 0xC6] } else { [0x8B; 4] };
+        self.write_memory(CFG_LP_QUAT, &lp_quat)?;
+        let quat_6x = if outputs.quaternion_6x { [0x20, 0x28, 0x30, 0x38] } else { [DINAA3; 4] };
+        self.write_memory(CFG_8, &quat_6x)?;
+
+        self.dmp_layout.outputs = outputs;
+        self.reset_fifo()
+    }
+
+    /// Sensor data the DMP currently pushes into the FIFO.
+    pub fn dmp_outputs(&self) -> DmpOutputs {
+        self.dmp_layout.outputs
+    }
+
+    /// Enable DMP tap detection.
+    /// Tap events are reported through `DmpPacket::tap`.
+    pub fn enable_dmp_tap(&mut self, config: TapConfig) -> Result<(), Error<I2c>> {
+        self.write_memory(CFG_20, &[DINAF8])?;
+        self.set_dmp_tap_threshold(config.axes, config.threshold)?;
+        self.set_dmp_tap_axes(config.axes)?;
+        self.set_dmp_tap_count(config.min_taps)?;
+        self.set_dmp_tap_time(config.time_ms)?;
+        self.set_dmp_tap_time_multi(config.multi_time_ms)?;
+        self.set_dmp_shake_reject(200, 40, 10)?;
+
+        self.dmp_layout.tap = true;
+        self.update_dmp_gesture()
+    }
+
+    /// Disable DMP tap detection.
+    pub fn disable_dmp_tap(&mut self) -> Result<(), Error<I2c>> {
+        self.write_memory(CFG_20, &[DINAD8])?;
+        self.dmp_layout.tap = false;
+        self.update_dmp_gesture()
+    }
+
+    /// Enable DMP display-orientation detection.
+    /// Orientation changes are reported through `DmpPacket::orientation`.
+    pub fn enable_dmp_orientation(&mut self) -> Result<(), Error<I2c>> {
+        self.write_memory(CFG_ANDROID_ORIENT_INT, &[DIND9])?;
+        self.dmp_layout.orientation = true;
+        self.update_dmp_gesture()
+    }
+
+    /// Disable DMP display-orientation detection.
+    pub fn disable_dmp_orientation(&mut self) -> Result<(), Error<I2c>> {
+        self.write_memory(CFG_ANDROID_ORIENT_INT, &[DINAD8])?;
+        self.dmp_layout.orientation = false;
+        self.update_dmp_gesture()
+    }
+
+    /// Set the tap threshold, in mg/ms, for the given axes.
+    pub fn set_dmp_tap_threshold(&mut self, axes: TapAxes, threshold: u16) -> Result<(), Error<I2c>> {
+        let threshold = threshold.min(1600) as u32;
+        let (full, partial) = match self.accel_full_scale()? {
+            AccelFullScale::G2 => (16384, 12288),
+            AccelFullScale::G4 => (8192, 6144),
+            AccelFullScale::G8 => (4096, 3072),
+            AccelFullScale::G16 => (2048, 1536),
+        };
+        let dmp_thresh = (threshold * full / DMP_SAMPLE_RATE) as u16;
+        let dmp_thresh_2 = (threshold * partial / DMP_SAMPLE_RATE) as u16;
+
+        if axes.x {
+            self.write_memory(DMP_TAP_THX, &dmp_thresh.to_be_bytes())?;
+            self.write_memory(D_1_36, &dmp_thresh_2.to_be_bytes())?;
+        }
+        if axes.y {
+            self.write_memory(DMP_TAP_THY, &dmp_thresh.to_be_bytes())?;
+            self.write_memory(D_1_40, &dmp_thresh_2.to_be_bytes())?;
+        }
+        if axes.z {
+            self.write_memory(DMP_TAP_THZ, &dmp_thresh.to_be_bytes())?;
+            self.write_memory(D_1_44, &dmp_thresh_2.to_be_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Select the axes on which taps are detected.
+    pub fn set_dmp_tap_axes(&mut self, axes: TapAxes) -> Result<(), Error<I2c>> {
+        let mut value = 0;
+        if axes.x { value |= 0x30 }
+        if axes.y { value |= 0x0C }
+        if axes.z { value |= 0x03 }
+        self.write_memory(D_1_72, &[value])
+    }
+
+    /// Set the minimum number of consecutive taps to report, from 1 to 4.
+    pub fn set_dmp_tap_count(&mut self, min_taps: u8) -> Result<(), Error<I2c>> {
+        let min_taps = min_taps.clamp(1, 4);
+        self.write_memory(D_1_79, &[min_taps - 1])
+    }
+
+    /// Set the minimum time between taps, in milliseconds.
+    pub fn set_dmp_tap_time(&mut self, time_ms: u16) -> Result<(), Error<I2c>> {
+        self.write_memory(DMP_TAPW_MIN, &dmp_ticks(time_ms).to_be_bytes())
+    }
+
+    /// Set the maximum time between taps counted together, in milliseconds.
+    pub fn set_dmp_tap_time_multi(&mut self, time_ms: u16) -> Result<(), Error<I2c>> {
+        self.write_memory(D_1_218, &dmp_ticks(time_ms).to_be_bytes())
+    }
+
+    /// Reject taps while the device is being shaken.
+    ///
+    /// `threshold` is the gyro rate in dps above which taps are rejected,
+    /// `time_ms` how long it must be exceeded and `timeout_ms` how long
+    /// rejection persists afterwards.
+    fn set_dmp_shake_reject(&mut self, threshold: u16, time_ms: u16, timeout_ms: u16) -> Result<(), Error<I2c>> {
+        let threshold = GYRO_SF / 1000 * threshold as u32;
+        self.write_memory(D_1_92, &threshold.to_be_bytes())?;
+        self.write_memory(D_1_90, &dmp_ticks(time_ms).to_be_bytes())?;
+        self.write_memory(D_1_88, &dmp_ticks(timeout_ms).to_be_bytes())
+    }
+
+    /// Enable or disable continuous gyro bias calibration.
+    ///
+    /// When enabled, the DMP re-learns the gyro bias whenever the
+    /// device has been motionless for a few seconds. MotionDriver 6.12
+    /// keeps the learned bias internal, so it cannot be read out.
+    pub fn enable_dmp_gyro_calibration(&mut self, enable: bool) -> Result<(), Error<I2c>> {
+        let regs = if enable {
+            [0xB8, 0xAA, 0xB3, 0x8D, 0xB4, 0x98, 0x0D, 0x35, 0x5D]
+        } else {
+            [0xB8, 0xAA, 0xAA, 0xAA, 0xB0, 0x88, 0xC3, 0xC5, 0xC7]
+        };
+        self.write_memory(CFG_MOTION_BIAS, &regs)
+    }
+
+    /// Supply a gyro bias to the DMP, such as one measured by the host and persisted.
+    pub fn set_dmp_gyro_bias(&mut self, bias: GyroBiases) -> Result<(), Error<I2c>> {
+        let mut buf = [0; 12];
+        for (i, value) in [bias.x, bias.y, bias.z].iter().enumerate() {
+            let scaled = ((*value as i64 * GYRO_SF as i64) >> 30) as i32;
+            buf[i * 4..i * 4 + 4].copy_from_slice(&scaled.to_be_bytes());
+        }
+        self.write_memory(D_EXT_GYRO_BIAS_X, &buf)
+    }
+
+    /// Number of steps counted by the DMP pedometer.
+    pub fn dmp_step_count(&mut self) -> Result<u32, Error<I2c>> {
+        self.require_pedometer()?;
+        let mut buf = [0; 4];
+        self.read_memory(D_PEDSTD_STEPCTR, &mut buf)?;
+        Ok(u32::from_be_bytes(buf))
+    }
+
+    /// Overwrite the DMP pedometer step count.
+    pub fn set_dmp_step_count(&mut self, count: u32) -> Result<(), Error<I2c>> {
+        self.require_pedometer()?;
+        self.write_memory(D_PEDSTD_STEPCTR, &count.to_be_bytes())
+    }
+
+    /// Time spent walking, as tracked by the DMP pedometer.
+    pub fn dmp_walk_time(&mut self) -> Result<Milliseconds<u32>, Error<I2c>> {
+        self.require_pedometer()?;
+        let mut buf = [0; 4];
+        self.read_memory(D_PEDSTD_TIMECTR, &mut buf)?;
+        Ok(Milliseconds(u32::from_be_bytes(buf).saturating_mul(PEDOMETER_TIME_UNIT_MS)))
+    }
+
+    /// Reset both the DMP pedometer step count and walk time.
+    pub fn reset_dmp_pedometer(&mut self) -> Result<(), Error<I2c>> {
+        self.set_dmp_step_count(0)?;
+        self.write_memory(D_PEDSTD_TIMECTR, &0u32.to_be_bytes())
+    }
+
+    fn require_pedometer(&mut self) -> Result<(), Error<I2c>> {
+        if self.current_firmware()?.supports_pedometer() {
+            Ok(())
+        } else {
+            Err(Error::UnsupportedByFirmware)
+        }
+    }
+
+    /// Push gesture data into the FIFO if any gesture feature is enabled.
+    fn update_dmp_gesture(&mut self) -> Result<(), Error<I2c>> {
+        let value = if self.dmp_layout.gesture() { DINA20 } else { DINAD8 };
+        self.write_memory(CFG_27, &[value])?;
+        self.reset_fifo()
+    }
+}
+
+/// Convert milliseconds to DMP sample periods.
+fn dmp_ticks(time_ms: u16) -> u16 {
+    time_ms / (1000 / DMP_SAMPLE_RATE as u16)
+}
@@ -0,0 +1,62 @@
+//! DMP memory locations for the bundled MotionDriver 6.12 image.
+//!
+//! These are specific to this firmware image and must be revisited
+//! if the image is ever replaced.
+
+pub(crate) const D_1_36: u16 = 256 + 36;
+pub(crate) const D_1_40: u16 = 256 + 40;
+pub(crate) const D_1_44: u16 = 256 + 44;
+pub(crate) const D_1_72: u16 = 256 + 72;
+pub(crate) const D_1_79: u16 = 256 + 79;
+pub(crate) const D_1_88: u16 = 256 + 88;
+pub(crate) const D_1_90: u16 = 256 + 90;
+pub(crate) const D_1_92: u16 = 256 + 92;
+pub(crate) const D_1_218: u16 = 256 + 218;
+
+/// Pedometer step count.
+pub(crate) const D_PEDSTD_STEPCTR: u16 = 768 + 0x60;
+/// Pedometer walk time, in units of `PEDOMETER_TIME_UNIT_MS`.
+pub(crate) const D_PEDSTD_TIMECTR: u16 = 964;
+pub(crate) const PEDOMETER_TIME_UNIT_MS: u32 = 20;
+
+pub(crate) const DMP_TAP_THX: u16 = 468;
+pub(crate) const DMP_TAP_THY: u16 = 472;
+pub(crate) const DMP_TAP_THZ: u16 = 476;
+pub(crate) const DMP_TAPW_MIN: u16 = 478;
+
+/// Gyro bias compensated by the DMP, three consecutive big-endian words.
+pub(crate) const D_EXT_GYRO_BIAS_X: u16 = 61 * 16;
+
+/// Selects the 3-axis quaternion output.
+pub(crate) const CFG_LP_QUAT: u16 = 2712;
+/// Selects the 6-axis quaternion output.
+pub(crate) const CFG_8: u16 = 2718;
+/// Selects raw or calibrated gyro output.
+pub(crate) const CFG_GYRO_RAW_DATA: u16 = 2722;
+/// Selects accel and gyro output.
+pub(crate) const CFG_15: u16 = 2727;
+
+/// Enables tap detection.
+pub(crate) const CFG_20: u16 = 2224;
+/// Enables gesture data in the FIFO.
+pub(crate) const CFG_27: u16 = 2742;
+/// Enables continuous gyro calibration while motionless.
+pub(crate) const CFG_MOTION_BIAS: u16 = 1208;
+/// Enables display-orientation events.
+pub(crate) const CFG_ANDROID_ORIENT_INT: u16 = 1853;
+
+/// Rate at which the DMP runs its algorithms, in Hz.
+pub(crate) const DMP_SAMPLE_RATE: u32 = 200;
+/// Gyro integration scale factor matching `DMP_SAMPLE_RATE`.
+pub(crate) const GYRO_SF: u32 = (46_850_825u64 * 200 / DMP_SAMPLE_RATE as u64) as u32;
+
+/// Instruction which pushes gesture data to the FIFO, at `CFG_27`.
+pub(crate) const DINA20: u8 = 0x20;
+/// Instruction which enables tap detection, at `CFG_20`.
+pub(crate) const DINAF8: u8 = 0xF8;
+/// Instruction which enables display-orientation events.
+pub(crate) const DIND9: u8 = 0xD9;
+/// No-op instruction, used to skip FIFO outputs.
+pub(crate) const DINAA3: u8 = 0xA3;
+/// Instruction which disables a patchable feature.
+pub(crate) const DINAD8: u8 = 0xD8;
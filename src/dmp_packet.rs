@@ -0,0 +1,141 @@
+use crate::accel::Accel;
+use crate::gyro::Gyro;
+use crate::quaternion::{QFormat, Quaternion};
+use crate::tap::TapEvent;
+use crate::orientation::Orientation;
+
+/// Sensor data the DMP pushes into the FIFO, applied with `Mpu6050::set_dmp_outputs()`.
+#[derive(Copy, Clone, Debug)]
+pub struct DmpOutputs {
+    /// Gyro-only (3-axis) quaternion.
+    pub quaternion_3x: bool,
+    /// Gyro and accel (6-axis) quaternion. Takes precedence over `quaternion_3x`.
+    pub quaternion_6x: bool,
+    pub accel: bool,
+    pub gyro: bool,
+    /// Push gyro readings with the DMP's bias compensation applied.
+    pub calibrated_gyro: bool,
+}
+
+impl DmpOutputs {
+    pub fn quaternion_only() -> Self {
+        Self {
+            quaternion_3x: false,
+            quaternion_6x: true,
+            accel: false,
+            gyro: false,
+            calibrated_gyro: false,
+        }
+    }
+
+    pub(crate) fn quaternion(&self) -> bool {
+        self.quaternion_3x || self.quaternion_6x
+    }
+}
+
+impl Default for DmpOutputs {
+    /// Outputs of the bundled firmware without further configuration.
+    fn default() -> Self {
+        Self {
+            quaternion_3x: false,
+            quaternion_6x: true,
+            accel: true,
+            gyro: true,
+            calibrated_gyro: true,
+        }
+    }
+}
+
+/// Fields the DMP pushes into the FIFO for each packet, in FIFO order.
+///
+/// Obtain the current layout from `Mpu6050::dmp_packet_layout()`.
+#[derive(Copy, Clone, Debug)]
+pub struct DmpPacketLayout {
+    pub(crate) outputs: DmpOutputs,
+    pub(crate) tap: bool,
+    pub(crate) orientation: bool,
+}
+
+impl Default for DmpPacketLayout {
+    /// Layout produced by the bundled firmware without further configuration.
+    fn default() -> Self {
+        Self {
+            outputs: DmpOutputs::default(),
+            tap: false,
+            orientation: false,
+        }
+    }
+}
+
+impl DmpPacketLayout {
+    pub(crate) fn gesture(&self) -> bool {
+        self.tap || self.orientation
+    }
+
+    /// Size of each packet in bytes.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        let mut len = 0;
+        if self.outputs.quaternion() { len += 16 }
+        if self.outputs.accel { len += 6 }
+        if self.outputs.gyro { len += 6 }
+        if self.gesture() { len += 4 }
+        len
+    }
+
+    /// Parse a single packet read from the FIFO.
+    #[allow(clippy::result_unit_err)]
+    pub fn parse(&self, bytes: &[u8]) -> Result<DmpPacket, ()> {
+        if bytes.len() != self.len() {
+            return Err(());
+        }
+
+        let mut packet = DmpPacket::default();
+        let mut i = 0;
+
+        if self.outputs.quaternion() {
+            packet.quaternion = Some(Quaternion::from_bytes_with_format(&bytes[i..i + 16], QFormat::Q30)?);
+            i += 16;
+        }
+
+        if self.outputs.accel {
+            packet.accel = Some(Accel::new(six(&bytes[i..])));
+            i += 6;
+        }
+
+        if self.outputs.gyro {
+            packet.gyro = Some(Gyro::new(six(&bytes[i..])));
+            i += 6;
+        }
+
+        if self.gesture() {
+            let gesture = &bytes[i..i + 4];
+            if self.tap && (gesture[1] & INT_SRC_TAP) != 0 {
+                packet.tap = TapEvent::from_byte(gesture[3]);
+            }
+            if self.orientation && (gesture[1] & INT_SRC_ANDROID_ORIENT) != 0 {
+                packet.orientation = Some(Orientation::from_byte(gesture[3]));
+            }
+        }
+
+        Ok(packet)
+    }
+}
+
+const INT_SRC_TAP: u8 = 0x01;
+const INT_SRC_ANDROID_ORIENT: u8 = 0x08;
+
+fn six(bytes: &[u8]) -> [u8; 6] {
+    [bytes[0], bytes[1], bytes[2], bytes[3], bytes[4], bytes[5]]
+}
+
+/// Contents of a single DMP FIFO packet.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct DmpPacket {
+    pub quaternion: Option<Quaternion>,
+    pub accel: Option<Accel>,
+    pub gyro: Option<Gyro>,
+    pub tap: Option<TapEvent>,
+    /// Set when the display orientation changed.
+    pub orientation: Option<Orientation>,
+}
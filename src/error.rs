@@ -11,6 +11,18 @@ pub enum Error<I2c>
     WriteError(<I2c as Write>::Error),
     WriteReadError(<I2c as WriteRead>::Error),
     WrongDevice,
+    /// The loaded DMP firmware does not provide the requested feature.
+    UnsupportedByFirmware,
+    /// The sensor is asleep; call `wake()` first.
+    Sleeping,
+    /// An external sensor did not acknowledge an auxiliary bus transfer.
+    AuxNack,
+    /// The auxiliary i2c master lost arbitration.
+    AuxArbitrationLost,
+    /// An auxiliary bus transfer did not complete, typically because the master is disabled.
+    AuxTimeout,
+    /// An external sensor's data would not fit in the EXT_SENS_DATA registers after that of lower-numbered slaves.
+    AuxDataOverflow,
 }
 
 impl<I2c> core::fmt::Debug for Error<I2c>
@@ -24,6 +36,12 @@ impl<I2c> core::fmt::Debug for Error<I2c>
             Error::WriteReadError(e) => f.debug_tuple("WriteReadError").field(e).finish(),
             Error::WriteError(e) => f.debug_tuple("WriteError").field(e).finish(),
             Error::WrongDevice => f.write_str("WrongDevice"),
+            Error::UnsupportedByFirmware => f.write_str("UnsupportedByFirmware"),
+            Error::Sleeping => f.write_str("Sleeping"),
+            Error::AuxNack => f.write_str("AuxNack"),
+            Error::AuxArbitrationLost => f.write_str("AuxArbitrationLost"),
+            Error::AuxTimeout => f.write_str("AuxTimeout"),
+            Error::AuxDataOverflow => f.write_str("AuxDataOverflow"),
         }
     }
 }
\ No newline at end of file
@@ -1,6 +1,9 @@
+use crate::accel::Accel;
+use crate::aux::{AuxSlave, EXT_SENS_DATA_LEN};
 
 
-#[derive(Debug, Copy, Clone)]
+
+#[derive(Debug, Copy, Clone, Default)]
 pub struct Fifo {
     pub temp: bool,
     pub xg: bool,
@@ -31,33 +34,197 @@ impl Fifo {
         }
     }
 
-    pub(crate) fn to_byte(&self) -> u8 {
+    pub(crate) fn to_byte(self) -> u8 {
         let mut byte = 0;
-        if self.temp  { byte |= (1 << 7) }
-        if self.xg    { byte |= (1 << 6) }
-        if self.yg    { byte |= (1 << 5) }
-        if self.zg    { byte |= (1 << 4) }
-        if self.accel { byte |= (1 << 3) }
-        if self.slv2  { byte |= (1 << 2) }
-        if self.slv1  { byte |= (1 << 1) }
-        if self.slv0  { byte |= (1 << 0) }
+        if self.temp  { byte |= 1 << 7 }
+        if self.xg    { byte |= 1 << 6 }
+        if self.yg    { byte |= 1 << 5 }
+        if self.zg    { byte |= 1 << 4 }
+        if self.accel { byte |= 1 << 3 }
+        if self.slv2  { byte |= 1 << 2 }
+        if self.slv1  { byte |= 1 << 1 }
+        if self.slv0  { byte |= 1 << 0 }
 
         byte
     }
 
 }
 
-impl Default for Fifo {
-    fn default() -> Self {
+/// Layout of the frames written to the FIFO for a given `Fifo` configuration.
+///
+/// The hardware writes enabled sources in register order: accel, temperature,
+/// gyro X, Y and Z, then the data read from auxiliary slaves 0 to 3.
+#[derive(Debug, Copy, Clone)]
+pub struct FifoFrameLayout {
+    fifo: Fifo,
+    slave3: bool,
+    slave_len: [usize; 4],
+}
+
+impl From<Fifo> for FifoFrameLayout {
+    fn from(fifo: Fifo) -> Self {
         Self {
-            temp: false,
-            xg: false,
-            yg: false,
-            zg: false,
-            accel: false,
-            slv2: false,
-            slv1: false,
-            slv0: false,
+            fifo,
+            slave3: false,
+            slave_len: [0; 4],
+        }
+    }
+}
+
+impl FifoFrameLayout {
+    /// Set the number of bytes read by an auxiliary slave.
+    pub fn with_slave_len(mut self, slave: AuxSlave, len: usize) -> Self {
+        self.slave_len[slave as usize] = len;
+        self
+    }
+
+    /// Include slave 3 data, which is enabled through `AuxMasterConfig::slave3_fifo` rather than `Fifo`.
+    pub fn with_slave3_fifo(mut self, enabled: bool) -> Self {
+        self.slave3 = enabled;
+        self
+    }
+
+    fn slave_total(&self) -> usize {
+        let mut len = 0;
+        if self.fifo.slv0 { len += self.slave_len[0] }
+        if self.fifo.slv1 { len += self.slave_len[1] }
+        if self.fifo.slv2 { len += self.slave_len[2] }
+        if self.slave3 { len += self.slave_len[3] }
+        len.min(EXT_SENS_DATA_LEN)
+    }
+
+    /// Size of each frame in bytes.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        let mut len = 0;
+        if self.fifo.accel { len += 6 }
+        if self.fifo.temp { len += 2 }
+        if self.fifo.xg { len += 2 }
+        if self.fifo.yg { len += 2 }
+        if self.fifo.zg { len += 2 }
+        len + self.slave_total()
+    }
+
+    /// Parse a single frame read from the FIFO.
+    #[allow(clippy::result_unit_err)]
+    pub fn parse(&self, bytes: &[u8]) -> Result<RawSample, ()> {
+        if bytes.len() != self.len() {
+            return Err(());
+        }
+        Ok(self.parse_frame(bytes))
+    }
+
+    /// Parse every complete frame in `bytes`, ignoring any trailing partial frame.
+    pub fn frames<'a>(&self, bytes: &'a [u8]) -> impl Iterator<Item = RawSample> + 'a {
+        let layout = *self;
+        let len = layout.len();
+        let count = bytes.len().checked_div(len).unwrap_or(0);
+        (0..count).map(move |n| layout.parse_frame(&bytes[n * len..(n + 1) * len]))
+    }
+
+    fn parse_frame(&self, bytes: &[u8]) -> RawSample {
+        let mut sample = RawSample::default();
+        let mut i = 0;
+
+        if self.fifo.accel {
+            let mut data = [0; 6];
+            data.copy_from_slice(&bytes[0..6]);
+            sample.accel = Some(Accel::new(data));
+            i += 6;
         }
+        if self.fifo.temp { sample.temp = Some(word(bytes, &mut i)) }
+        if self.fifo.xg { sample.gyro_x = Some(word(bytes, &mut i)) }
+        if self.fifo.yg { sample.gyro_y = Some(word(bytes, &mut i)) }
+        if self.fifo.zg { sample.gyro_z = Some(word(bytes, &mut i)) }
+
+        sample.slave_len = bytes.len() - i;
+        sample.slave_data[..sample.slave_len].copy_from_slice(&bytes[i..]);
+        sample
     }
-}
\ No newline at end of file
+}
+
+fn word(bytes: &[u8], i: &mut usize) -> i16 {
+    let value = i16::from_be_bytes([bytes[*i], bytes[*i + 1]]);
+    *i += 2;
+    value
+}
+
+/// A single frame of raw sensor data read from the FIFO.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct RawSample {
+    pub accel: Option<Accel>,
+    pub temp: Option<i16>,
+    pub gyro_x: Option<i16>,
+    pub gyro_y: Option<i16>,
+    pub gyro_z: Option<i16>,
+    slave_data: [u8; EXT_SENS_DATA_LEN],
+    slave_len: usize,
+}
+
+impl RawSample {
+    /// Bytes read from the enabled auxiliary slaves, in slave order.
+    pub fn slave_data(&self) -> &[u8] {
+        &self.slave_data[..self.slave_len]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn len_counts_enabled_sources() {
+        assert_eq!(FifoFrameLayout::from(Fifo::all_disabled()).len(), 0);
+
+        let fifo = Fifo { accel: true, ..Default::default() };
+        assert_eq!(FifoFrameLayout::from(fifo).len(), 6);
+
+        let fifo = Fifo { temp: true, xg: true, yg: true, zg: true, accel: true, ..Default::default() };
+        assert_eq!(FifoFrameLayout::from(fifo).len(), 14);
+
+        // slave lengths only count once the slave is enabled
+        let fifo = Fifo { xg: true, slv1: true, ..Default::default() };
+        let layout = FifoFrameLayout::from(fifo)
+            .with_slave_len(AuxSlave::Slv0, 4)
+            .with_slave_len(AuxSlave::Slv1, 6);
+        assert_eq!(layout.len(), 8);
+
+        let layout = layout.with_slave_len(AuxSlave::Slv3, 3);
+        assert_eq!(layout.len(), 8);
+        assert_eq!(layout.with_slave3_fifo(true).len(), 11);
+    }
+
+    #[test]
+    fn parse_in_register_order() {
+        let fifo = Fifo { temp: true, zg: true, accel: true, slv0: true, ..Default::default() };
+        let layout = FifoFrameLayout::from(fifo).with_slave_len(AuxSlave::Slv0, 2);
+        let bytes = [0x00, 0x01, 0x00, 0x02, 0xFF, 0xFD, 0x12, 0x34, 0x80, 0x00, 0xAB, 0xCD];
+
+        let sample = layout.parse(&bytes).unwrap();
+        let accel = sample.accel.unwrap();
+        assert_eq!((accel.x(), accel.y(), accel.z()), (1, 2, -3));
+        assert_eq!(sample.temp, Some(0x1234));
+        assert_eq!(sample.gyro_x, None);
+        assert_eq!(sample.gyro_y, None);
+        assert_eq!(sample.gyro_z, Some(i16::MIN));
+        assert_eq!(sample.slave_data(), &[0xAB, 0xCD]);
+
+        assert!(layout.parse(&bytes[1..]).is_err());
+    }
+
+    #[test]
+    fn frames_skip_trailing_partial_frame() {
+        let fifo = Fifo { xg: true, yg: true, ..Default::default() };
+        let layout = FifoFrameLayout::from(fifo);
+        let bytes = [0x00, 0x01, 0x00, 0x02, 0x00, 0x03, 0x00, 0x04, 0x00];
+
+        let mut frames = layout.frames(&bytes);
+        let first = frames.next().unwrap();
+        assert_eq!((first.gyro_x, first.gyro_y), (Some(1), Some(2)));
+        let second = frames.next().unwrap();
+        assert_eq!((second.gyro_x, second.gyro_y), (Some(3), Some(4)));
+        assert!(frames.next().is_none());
+
+        assert_eq!(FifoFrameLayout::from(Fifo::all_disabled()).frames(&bytes).count(), 0);
+    }
+}
@@ -3,6 +3,7 @@ use embedded_hal::blocking::i2c::{Write, WriteRead};
 use crate::error::Error;
 use crate::registers::Register;
 use crate::dmp_firmware::FIRMWARE;
+use crate::firmware_version::{FirmwareVersion, Fingerprint, SIGNATURE_LEN, SIGNATURE_START};
 
 const BANK_SIZE: usize = 256;
 const CHUNK_SIZE: usize = 16;
@@ -14,7 +15,9 @@ impl<'clock, I2c, Clock> Mpu6050<'clock, I2c, Clock>
           Clock: embedded_time::Clock {
     pub fn load_firmware(&mut self) -> Result<(), Error<I2c>> {
         log::info!( "loading firmware");
-        self.write_memory(&FIRMWARE)
+        self.write_memory(0, &FIRMWARE)?;
+        self.firmware = Some(FirmwareVersion::MotionDriver612);
+        Ok(())
         //self.boot_firmware()
     }
 
@@ -22,24 +25,61 @@ impl<'clock, I2c, Clock> Mpu6050<'clock, I2c, Clock>
         self.write( &[ Register::PrgmStart as u8, 0x04, 0x00 ])
     }
 
-    fn write_memory(&mut self, data: &[u8]) -> Result<(), Error<I2c>> {
-        for ( bank, chunk ) in data.chunks(BANK_SIZE).enumerate() {
-            self.write_bank(bank as u8, chunk)?;
+    /// Identify the DMP firmware currently resident in memory.
+    ///
+    /// Reads back a code-only region of DMP memory bank by bank and
+    /// compares its fingerprint against the known firmware images.
+    pub fn firmware_version(&mut self) -> Result<FirmwareVersion, Error<I2c>> {
+        let mut fingerprint = Fingerprint::new();
+        let mut buf = [0; BANK_SIZE];
+        for bank in (0..SIGNATURE_LEN).step_by(BANK_SIZE) {
+            self.read_memory(SIGNATURE_START + bank as u16, &mut buf)?;
+            fingerprint.update(&buf);
+        }
+        let version = FirmwareVersion::from_fingerprint(fingerprint.finish());
+        self.firmware = Some(version);
+        Ok(version)
+    }
+
+    /// Firmware version last loaded or identified, probing memory if unknown.
+    pub(crate) fn current_firmware(&mut self) -> Result<FirmwareVersion, Error<I2c>> {
+        match self.firmware {
+            Some(version) => Ok(version),
+            None => self.firmware_version(),
         }
-        Ok(())
     }
 
-    fn write_bank(&mut self, bank: u8, data: &[u8]) -> Result<(), Error<I2c>> {
-        self.set_bank(bank);
+    /// Read DMP memory starting at `address`, which spans bank and offset.
+    pub(crate) fn read_memory(&mut self, address: u16, buf: &mut [u8]) -> Result<(), Error<I2c>> {
+        let mut offset = 0;
+        while offset < buf.len() {
+            let addr = address as usize + offset;
+            let len = (BANK_SIZE - addr % BANK_SIZE)
+                .min(CHUNK_SIZE)
+                .min(buf.len() - offset);
+            self.set_bank((addr / BANK_SIZE) as u8)?;
+            self.set_memory_start_address((addr % BANK_SIZE) as u8)?;
+            self.read_registers(Register::MemRw, &mut buf[offset..offset + len])?;
+            offset += len;
+        }
+        Ok(())
+    }
 
-        for ( i, chunk ) in data.chunks( CHUNK_SIZE).enumerate() {
+    /// Write DMP memory starting at `address`, which spans bank and offset.
+    pub(crate) fn write_memory(&mut self, address: u16, data: &[u8]) -> Result<(), Error<I2c>> {
+        let mut offset = 0;
+        while offset < data.len() {
+            let addr = address as usize + offset;
+            let len = (BANK_SIZE - addr % BANK_SIZE)
+                .min(CHUNK_SIZE)
+                .min(data.len() - offset);
             let mut prolog_and_chunk: [u8; CHUNK_SIZE + 1] = [0; CHUNK_SIZE + 1];
             prolog_and_chunk[0] = Register::MemRw as u8;
-            for (i, b) in chunk.iter().enumerate() {
-                prolog_and_chunk[i+1] = *b;
-            }
-            self.set_memory_start_address((i * CHUNK_SIZE) as u8)?;
-            self.write( &prolog_and_chunk )?;
+            prolog_and_chunk[1..=len].copy_from_slice(&data[offset..offset + len]);
+            self.set_bank((addr / BANK_SIZE) as u8)?;
+            self.set_memory_start_address((addr % BANK_SIZE) as u8)?;
+            self.write(&prolog_and_chunk[..=len])?;
+            offset += len;
         }
 
         log::info!("write {}", data.len());
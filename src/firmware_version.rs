@@ -0,0 +1,68 @@
+use crate::dmp_firmware::FIRMWARE;
+
+/// First DMP memory address covered by the fingerprint (bank 5).
+///
+/// The DMP program starts at 0x0400, but the driver patches several
+/// configuration bytes in bank 4 and in the upper banks when enabling
+/// features. Banks 5 and 6 contain only code and are never rewritten.
+pub(crate) const SIGNATURE_START: u16 = 0x0500;
+
+/// Number of bytes covered by the fingerprint (banks 5 and 6).
+pub(crate) const SIGNATURE_LEN: usize = 512;
+
+const FNV_OFFSET: u32 = 0x811C_9DC5;
+const FNV_PRIME: u32 = 0x0100_0193;
+
+const MOTION_DRIVER_612: u32 = fingerprint_firmware(SIGNATURE_START as usize, SIGNATURE_LEN);
+
+/// DMP firmware image identified from the contents of DMP memory.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FirmwareVersion {
+    /// InvenSense Embedded MotionDriver 6.12, as bundled with this crate.
+    MotionDriver612,
+    /// Unrecognized memory contents, along with their fingerprint.
+    Unknown(u32),
+}
+
+impl FirmwareVersion {
+    /// Whether the image maintains a pedometer step count and walk time.
+    pub fn supports_pedometer(&self) -> bool {
+        matches!(self, FirmwareVersion::MotionDriver612)
+    }
+
+    pub(crate) fn from_fingerprint(fingerprint: u32) -> Self {
+        match fingerprint {
+            MOTION_DRIVER_612 => FirmwareVersion::MotionDriver612,
+            _ => FirmwareVersion::Unknown(fingerprint),
+        }
+    }
+}
+
+/// Incremental FNV-1a hash of DMP memory, fed one chunk at a time.
+pub(crate) struct Fingerprint(u32);
+
+impl Fingerprint {
+    pub(crate) fn new() -> Self {
+        Self(FNV_OFFSET)
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        for b in data {
+            self.0 = (self.0 ^ *b as u32).wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    pub(crate) fn finish(&self) -> u32 {
+        self.0
+    }
+}
+
+const fn fingerprint_firmware(start: usize, len: usize) -> u32 {
+    let mut hash = FNV_OFFSET;
+    let mut i = start;
+    while i < start + len {
+        hash = (hash ^ FIRMWARE[i] as u32).wrapping_mul(FNV_PRIME);
+        i += 1;
+    }
+    hash
+}
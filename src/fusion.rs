@@ -0,0 +1,152 @@
+use crate::quaternion::Quaternion;
+use crate::magnetometer::MagneticField;
+
+use core::f32::consts::PI;
+
+#[derive(Debug, Copy, Clone)]
+pub struct MagFusionConfig {
+    /// Fraction of the measured yaw error corrected on each update, between 0 and 1.
+    pub gain: f32,
+    /// Magnetic declination in degrees, positive east, so that yaw references true north.
+    pub declination: f32,
+}
+
+impl Default for MagFusionConfig {
+    fn default() -> Self {
+        Self {
+            gain: 0.02,
+            declination: 0.0,
+        }
+    }
+}
+
+/// Corrects the yaw drift of the 6-axis DMP quaternion using a magnetometer.
+///
+/// Only the rotation about the vertical axis is adjusted; pitch and roll are
+/// left as computed by the DMP. The magnetometer readings must be expressed
+/// in the accel and gyro axes, and should be calibrated.
+///
+/// The corrected world frame has x pointing north and z pointing up.
+#[derive(Debug, Copy, Clone)]
+pub struct MagFusion {
+    config: MagFusionConfig,
+    yaw_offset: Option<f32>,
+}
+
+impl MagFusion {
+    pub fn new(config: MagFusionConfig) -> Self {
+        Self {
+            config,
+            yaw_offset: None,
+        }
+    }
+
+    /// Fold a magnetometer reading into the yaw correction, and return the corrected quaternion.
+    pub fn update(&mut self, q: Quaternion, mag: MagneticField) -> Quaternion {
+        // magnetic field in the DMP world frame
+        let [x, y, _] = q.rotate_vector([mag.x, mag.y, mag.z]);
+
+        if x * x + y * y > f32::EPSILON {
+            // rotate the world so that magnetic north lies `declination` east of x
+            let target = -(libm::atan2f(y, x) + self.config.declination.to_radians());
+            let offset = match self.yaw_offset {
+                Some(offset) => offset + self.config.gain * wrap(target - offset),
+                None => target,
+            };
+            self.yaw_offset = Some(wrap(offset));
+        }
+
+        self.correct(q)
+    }
+
+    /// Apply the current yaw correction without a new magnetometer reading.
+    pub fn correct(&self, q: Quaternion) -> Quaternion {
+        let offset = self.yaw_offset.unwrap_or(0.0);
+        // rotation about world z, applied after `q`
+        Quaternion::from_axis_angle([0.0, 0.0, 1.0], offset) * q
+    }
+
+    /// Current yaw correction in radians, once a reading has been processed.
+    pub fn yaw_offset(&self) -> Option<f32> {
+        self.yaw_offset
+    }
+
+    /// Forget the current correction; the next reading is applied in full.
+    pub fn reset(&mut self) {
+        self.yaw_offset = None;
+    }
+}
+
+/// Wrap an angle into -PI..PI.
+fn wrap(angle: f32) -> f32 {
+    let mut angle = libm::fmodf(angle + PI, 2.0 * PI);
+    if angle < 0.0 {
+        angle += 2.0 * PI;
+    }
+    angle - PI
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gravity::Gravity;
+
+    const DECLINATION: f32 = 10.0;
+
+    /// Reading of a horizontal field whose magnetic north lies `DECLINATION` east of true north,
+    /// for a device whose true orientation is `device`.
+    fn reading(device: Quaternion) -> MagneticField {
+        let (s, c) = libm::sincosf(DECLINATION.to_radians());
+        // x is north, z is up, so east is -y
+        let [x, y, z] = device.conjugate().rotate_vector([40.0 * c, -40.0 * s, 0.0]);
+        MagneticField { x, y, z }
+    }
+
+    fn yaw(angle: f32) -> Quaternion {
+        Quaternion::from_axis_angle([0.0, 0.0, 1.0], angle.to_radians())
+    }
+
+    #[test]
+    fn converges_to_true_north() {
+        let mut fusion = MagFusion::new(MagFusionConfig { gain: 0.2, declination: DECLINATION });
+        // the device points x at true north; the first reading is applied in full
+        let device = Quaternion::identity();
+        fusion.update(device, reading(device));
+        assert!(fusion.yaw_offset().unwrap().abs() < 1e-4);
+
+        // then the DMP drifts 50 degrees, which is corrected gradually
+        let dmp = yaw(50.0);
+        let first = fusion.update(dmp, reading(device));
+        assert!(first.rotate_vector([1.0, 0.0, 0.0])[1] > 0.1);
+
+        let mut corrected = first;
+        for _ in 0..100 {
+            corrected = fusion.update(dmp, reading(device));
+        }
+        let [x, y, z] = corrected.rotate_vector([1.0, 0.0, 0.0]);
+        assert!((x - 1.0).abs() < 1e-4 && y.abs() < 1e-3 && z.abs() < 1e-4, "{:?}", [x, y, z]);
+        assert!((fusion.yaw_offset().unwrap() + 50f32.to_radians()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn keeps_pitch_and_roll() {
+        let mut fusion = MagFusion::new(MagFusionConfig { gain: 1.0, declination: DECLINATION });
+        let tilt = Quaternion::from_axis_angle([0.0, 1.0, 0.0], 0.2)
+            * Quaternion::from_axis_angle([1.0, 0.0, 0.0], 0.3);
+        let device = yaw(-30.0) * tilt;
+        let dmp = yaw(20.0) * tilt;
+
+        let corrected = fusion.update(dmp, reading(device));
+        let (expected, actual) = (Gravity::from(dmp), Gravity::from(corrected));
+        assert!((expected.x - actual.x).abs() < 1e-5);
+        assert!((expected.y - actual.y).abs() < 1e-5);
+        assert!((expected.z - actual.z).abs() < 1e-5);
+
+        // and the yaw now matches the device
+        let forward = corrected.rotate_vector([1.0, 0.0, 0.0]);
+        let truth = device.rotate_vector([1.0, 0.0, 0.0]);
+        for (a, b) in forward.iter().zip(truth.iter()) {
+            assert!((a - b).abs() < 1e-4, "{:?} != {:?}", forward, truth);
+        }
+    }
+}
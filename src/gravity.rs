@@ -1,4 +1,5 @@
 use crate::quaternion::Quaternion;
+use crate::magnetometer::MagneticField;
 
 #[derive(Debug, Copy, Clone)]
 pub struct Gravity {
@@ -8,7 +9,41 @@ pub struct Gravity {
 }
 
 impl Gravity {
+    /// Tilt-compensated magnetic heading of the x axis, in degrees clockwise from magnetic north (0..360).
+    ///
+    /// The magnetometer reading must be expressed in the accel and gyro axes, and should be calibrated.
+    /// Down is taken as the opposite of gravity, so with the device flat z points up and y to the
+    /// left of x: facing east, north lies along +y.
+    pub fn heading(&self, mag: MagneticField) -> f32 {
+        // north-east-down frame, expressed in the sensor axes
+        let norm = libm::sqrtf(self.x * self.x + self.y * self.y + self.z * self.z);
+        let down = [-self.x / norm, -self.y / norm, -self.z / norm];
+        let east = cross(down, [mag.x, mag.y, mag.z]);
+        let north = cross(east, down);
 
+        let heading = libm::atan2f(east[0], north[0]).to_degrees();
+        wrap_degrees(heading)
+    }
+
+    /// Heading in degrees clockwise from true north (0..360), given the magnetic declination in degrees, positive east.
+    pub fn true_heading(&self, mag: MagneticField, declination: f32) -> f32 {
+        wrap_degrees(self.heading(mag) + declination)
+    }
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn wrap_degrees(angle: f32) -> f32 {
+    let angle = libm::fmodf(angle, 360.0);
+    let angle = if angle < 0.0 { angle + 360.0 } else { angle };
+    // tiny negative angles round up to 360
+    if angle >= 360.0 { 0.0 } else { angle }
 }
 
 impl From<Quaternion> for Gravity {
@@ -20,3 +55,51 @@ impl From<Quaternion> for Gravity {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FLAT: Gravity = Gravity { x: 0.0, y: 0.0, z: 1.0 };
+
+    fn field(x: f32, y: f32, z: f32) -> MagneticField {
+        MagneticField { x, y, z }
+    }
+
+    fn assert_heading(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-3, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn flat_heading_is_clockwise_from_north() {
+        // flat, z points up, so y points to the left of x: facing east, north is along +y
+        assert_heading(FLAT.heading(field(20.0, 0.0, -40.0)), 0.0);
+        assert_heading(FLAT.heading(field(0.0, 20.0, -40.0)), 90.0);
+        assert_heading(FLAT.heading(field(-20.0, 0.0, -40.0)), 180.0);
+        assert_heading(FLAT.heading(field(0.0, -20.0, -40.0)), 270.0);
+    }
+
+    #[test]
+    fn tilt_is_compensated() {
+        let tilt = Quaternion::from_axis_angle([0.0, 1.0, 0.0], 0.3)
+            * Quaternion::from_axis_angle([1.0, 0.0, 0.0], 0.4);
+        // turned 30 degrees clockwise, seen from above
+        let device = Quaternion::from_axis_angle([0.0, 0.0, 1.0], (-30f32).to_radians()) * tilt;
+
+        let [x, y, z] = device.conjugate().rotate_vector([20.0, 0.0, -40.0]);
+        let gravity = Gravity::from(device);
+        assert_heading(gravity.heading(field(x, y, z)), 30.0);
+    }
+
+    #[test]
+    fn declination_is_added_east_positive() {
+        let mag = field(20.0, 0.0, -40.0);
+        assert_heading(FLAT.true_heading(mag, 10.0), 10.0);
+        assert_heading(FLAT.true_heading(mag, -10.0), 350.0);
+
+        // 355 degrees magnetic wraps around to 5 degrees true
+        let (s, c) = libm::sincosf(355f32.to_radians());
+        let mag = field(20.0 * c, 20.0 * s, -40.0);
+        assert_heading(FLAT.true_heading(mag, 10.0), 5.0);
+    }
+}
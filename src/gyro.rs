@@ -1,3 +1,4 @@
+use crate::power::StandbyAxes;
 
 
 #[derive(Copy, Clone, Debug)]
@@ -5,6 +6,7 @@ pub struct Gyro {
     x: i16,
     y: i16,
     z: i16,
+    standby: [bool; 3],
 }
 
 impl Gyro {
@@ -16,9 +18,19 @@ impl Gyro {
             x: i16::from_be_bytes(x),
             y: i16::from_be_bytes(y),
             z: i16::from_be_bytes(z),
+            standby: [false; 3],
         }
     }
 
+    /// Flag axes in standby, whose registers would only hold stale values.
+    pub(crate) fn with_standby(mut self, standby: &StandbyAxes) -> Self {
+        self.standby = [standby.gyro_x, standby.gyro_y, standby.gyro_z];
+        if self.standby[0] { self.x = 0 }
+        if self.standby[1] { self.y = 0 }
+        if self.standby[2] { self.z = 0 }
+        self
+    }
+
     pub fn x(&self) -> i16 {
         self.x
     }
@@ -30,12 +42,29 @@ impl Gyro {
     pub fn z(&self) -> i16 {
         self.z
     }
+
+    /// Whether the X axis was in standby, in which case `x()` reads as zero.
+    pub fn x_standby(&self) -> bool {
+        self.standby[0]
+    }
+
+    /// Whether the Y axis was in standby, in which case `y()` reads as zero.
+    pub fn y_standby(&self) -> bool {
+        self.standby[1]
+    }
+
+    /// Whether the Z axis was in standby, in which case `z()` reads as zero.
+    pub fn z_standby(&self) -> bool {
+        self.standby[2]
+    }
 }
 
-struct GyroBiases {
-    x: i16,
-    y: i16,
-    z: i16,
+/// Gyro biases in degrees per second, as Q16 fixed-point.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct GyroBiases {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
 }
 
 #[derive(Copy, Clone, Debug)]
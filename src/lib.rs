@@ -9,12 +9,26 @@ pub mod gyro;
 pub mod fifo;
 pub mod clock_source;
 pub mod config;
+pub mod power;
+pub mod motion;
+// `aux` is a reserved file name on Windows.
+#[path = "auxiliary/mod.rs"]
+pub mod aux;
 mod dmp_firmware;
 mod firmware_loader;
+pub mod firmware_version;
+mod dmp;
+mod dmp_keys;
+pub mod dmp_packet;
+pub mod tap;
+pub mod orientation;
 pub mod quaternion;
 pub mod gravity;
 pub mod yaw_pitch_roll;
 pub mod euler;
+pub mod magnetometer;
+pub mod mag_calibration;
+pub mod fusion;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,339 @@
+use crate::magnetometer::MagneticField;
+
+/// Number of ellipsoid coefficients fitted.
+const PARAMS: usize = 9;
+
+/// Minimum number of samples before attempting a fit.
+pub const MIN_CALIBRATION_SAMPLES: u32 = 32;
+
+/// Hard- and soft-iron correction for a magnetometer.
+///
+/// A corrected reading is `matrix * (raw - offset)`, which lies on a sphere
+/// of radius `field`.
+#[derive(Debug, Copy, Clone)]
+pub struct MagCalibration {
+    /// Hard-iron offset in microtesla.
+    pub offset: [f32; 3],
+    /// Soft-iron correction matrix.
+    pub matrix: [[f32; 3]; 3],
+    /// Strength of the fitted field in microtesla.
+    pub field: f32,
+    /// RMS deviation of the samples from the fitted ellipsoid, relative to its radius.
+    ///
+    /// Good calibrations are typically within a few percent (0.02 - 0.05).
+    pub fit_error: f32,
+}
+
+impl Default for MagCalibration {
+    fn default() -> Self {
+        Self {
+            offset: [0.0; 3],
+            matrix: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+            field: 0.0,
+            fit_error: 0.0,
+        }
+    }
+}
+
+impl MagCalibration {
+    pub fn apply(&self, mag: MagneticField) -> MagneticField {
+        let v = [mag.x - self.offset[0], mag.y - self.offset[1], mag.z - self.offset[2]];
+        let m = &self.matrix;
+        MagneticField {
+            x: m[0][0] * v[0] + m[0][1] * v[1] + m[0][2] * v[2],
+            y: m[1][0] * v[0] + m[1][1] * v[1] + m[1][2] * v[2],
+            z: m[2][0] * v[0] + m[2][1] * v[1] + m[2][2] * v[2],
+        }
+    }
+}
+
+/// Collects magnetometer samples while the device is rotated, and fits an ellipsoid to them.
+///
+/// Samples are folded into the normal equations of the fit as they arrive,
+/// so no sample buffer is kept. Rotate the device through as many
+/// orientations as possible before calling `fit()`.
+#[derive(Debug, Clone)]
+pub struct MagCalibrator {
+    normal: [[f64; PARAMS]; PARAMS],
+    rhs: [f64; PARAMS],
+    count: u32,
+    scale: f64,
+}
+
+impl Default for MagCalibrator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MagCalibrator {
+    pub fn new() -> Self {
+        Self {
+            normal: [[0.0; PARAMS]; PARAMS],
+            rhs: [0.0; PARAMS],
+            count: 0,
+            scale: 0.0,
+        }
+    }
+
+    pub fn add(&mut self, mag: MagneticField) {
+        if self.count == 0 {
+            // keep the sums well-conditioned regardless of the sensor units
+            self.scale = mag.magnitude() as f64;
+            if self.scale <= 0.0 {
+                return;
+            }
+        }
+        let row = Self::row(mag, self.scale);
+        for i in 0..PARAMS {
+            for j in 0..PARAMS {
+                self.normal[i][j] += row[i] * row[j];
+            }
+            self.rhs[i] += row[i];
+        }
+        self.count += 1;
+    }
+
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> u32 {
+        self.count
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Fit the ellipsoid `x'Ax + 2g'x = 1` to the samples collected so far.
+    ///
+    /// Returns `None` with too few samples, or when they do not describe an
+    /// ellipsoid (for instance if the device was only rotated about one axis).
+    pub fn fit(&self) -> Option<MagCalibration> {
+        if self.count < MIN_CALIBRATION_SAMPLES {
+            return None;
+        }
+        let p = solve(self.normal, self.rhs)?;
+
+        let a = [
+            [p[0], p[3], p[4]],
+            [p[3], p[1], p[5]],
+            [p[4], p[5], p[2]],
+        ];
+        let g = [p[6], p[7], p[8]];
+
+        // center = -A^-1 g
+        let inv = invert3(&a)?;
+        let center = [
+            -(inv[0][0] * g[0] + inv[0][1] * g[1] + inv[0][2] * g[2]),
+            -(inv[1][0] * g[0] + inv[1][1] * g[1] + inv[1][2] * g[2]),
+            -(inv[2][0] * g[0] + inv[2][1] * g[1] + inv[2][2] * g[2]),
+        ];
+        // shifted to the center, the ellipsoid is u'Au = k
+        let k = 1.0 - (g[0] * center[0] + g[1] * center[1] + g[2] * center[2]);
+        if k <= 0.0 {
+            return None;
+        }
+
+        let (values, vectors) = eigen3(a);
+        if values.iter().any(|v| *v <= 0.0) {
+            return None;
+        }
+        let values = [values[0] / k, values[1] / k, values[2] / k];
+        // sphere radius preserving the volume of the ellipsoid
+        let radius = libm::pow(values[0] * values[1] * values[2], -1.0 / 6.0);
+
+        // matrix = radius * sqrt(A / k)
+        let mut matrix = [[0.0; 3]; 3];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, m) in row.iter_mut().enumerate() {
+                let mut sum = 0.0;
+                for (n, value) in values.iter().enumerate() {
+                    sum += vectors[i][n] * libm::sqrt(*value) * vectors[j][n];
+                }
+                *m = (radius * sum) as f32;
+            }
+        }
+
+        // sum((d'p - 1)^2) = p'Mp - 2p'b + N, where d'p - 1 ~= 2k * relative radius error
+        let mut residual = self.count as f64;
+        for i in 0..PARAMS {
+            let mp: f64 = self.normal[i].iter().zip(p.iter()).map(|(m, p)| m * p).sum();
+            residual += p[i] * (mp - 2.0 * self.rhs[i]);
+        }
+        let fit_error = libm::sqrt(residual.max(0.0) / self.count as f64) / (2.0 * k);
+
+        Some(MagCalibration {
+            offset: [
+                (center[0] * self.scale) as f32,
+                (center[1] * self.scale) as f32,
+                (center[2] * self.scale) as f32,
+            ],
+            matrix,
+            field: (radius * self.scale) as f32,
+            fit_error: fit_error as f32,
+        })
+    }
+
+    fn row(mag: MagneticField, scale: f64) -> [f64; PARAMS] {
+        let x = mag.x as f64 / scale;
+        let y = mag.y as f64 / scale;
+        let z = mag.z as f64 / scale;
+        [x * x, y * y, z * z, 2.0 * x * y, 2.0 * x * z, 2.0 * y * z, 2.0 * x, 2.0 * y, 2.0 * z]
+    }
+}
+
+/// Gaussian elimination with partial pivoting.
+#[allow(clippy::needless_range_loop)]
+fn solve(mut m: [[f64; PARAMS]; PARAMS], mut b: [f64; PARAMS]) -> Option<[f64; PARAMS]> {
+    for col in 0..PARAMS {
+        let pivot = (col..PARAMS)
+            .max_by(|i, j| libm::fabs(m[*i][col]).total_cmp(&libm::fabs(m[*j][col])))?;
+        if libm::fabs(m[pivot][col]) < 1e-12 {
+            return None;
+        }
+        m.swap(col, pivot);
+        b.swap(col, pivot);
+        for row in col + 1..PARAMS {
+            let f = m[row][col] / m[col][col];
+            for k in col..PARAMS {
+                m[row][k] -= f * m[col][k];
+            }
+            b[row] -= f * b[col];
+        }
+    }
+    let mut x = [0.0; PARAMS];
+    for row in (0..PARAMS).rev() {
+        let mut sum = b[row];
+        for k in row + 1..PARAMS {
+            sum -= m[row][k] * x[k];
+        }
+        x[row] = sum / m[row][row];
+    }
+    Some(x)
+}
+
+fn invert3(a: &[[f64; 3]; 3]) -> Option<[[f64; 3]; 3]> {
+    let c00 = a[1][1] * a[2][2] - a[1][2] * a[2][1];
+    let c01 = a[1][2] * a[2][0] - a[1][0] * a[2][2];
+    let c02 = a[1][0] * a[2][1] - a[1][1] * a[2][0];
+    let det = a[0][0] * c00 + a[0][1] * c01 + a[0][2] * c02;
+    if libm::fabs(det) < 1e-18 {
+        return None;
+    }
+    Some([
+        [c00 / det, (a[0][2] * a[2][1] - a[0][1] * a[2][2]) / det, (a[0][1] * a[1][2] - a[0][2] * a[1][1]) / det],
+        [c01 / det, (a[0][0] * a[2][2] - a[0][2] * a[2][0]) / det, (a[0][2] * a[1][0] - a[0][0] * a[1][2]) / det],
+        [c02 / det, (a[0][1] * a[2][0] - a[0][0] * a[2][1]) / det, (a[0][0] * a[1][1] - a[0][1] * a[1][0]) / det],
+    ])
+}
+
+/// Eigen-decomposition of a symmetric 3x3 matrix by Jacobi rotations.
+///
+/// Returns the eigenvalues, and the eigenvectors as the columns of a matrix.
+#[allow(clippy::needless_range_loop)]
+fn eigen3(mut a: [[f64; 3]; 3]) -> ([f64; 3], [[f64; 3]; 3]) {
+    let mut v = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    for _ in 0..32 {
+        let off = a[0][1] * a[0][1] + a[0][2] * a[0][2] + a[1][2] * a[1][2];
+        if off < 1e-24 {
+            break;
+        }
+        for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+            if libm::fabs(a[p][q]) < 1e-30 {
+                continue;
+            }
+            let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+            let t = theta.signum() / (libm::fabs(theta) + libm::sqrt(theta * theta + 1.0));
+            let c = 1.0 / libm::sqrt(t * t + 1.0);
+            let s = t * c;
+            for k in 0..3 {
+                let akp = a[k][p];
+                let akq = a[k][q];
+                a[k][p] = c * akp - s * akq;
+                a[k][q] = s * akp + c * akq;
+            }
+            for k in 0..3 {
+                let apk = a[p][k];
+                let aqk = a[q][k];
+                a[p][k] = c * apk - s * aqk;
+                a[q][k] = s * apk + c * aqk;
+            }
+            for row in v.iter_mut() {
+                let vp = row[p];
+                let vq = row[q];
+                row[p] = c * vp - s * vq;
+                row[q] = s * vp + c * vq;
+            }
+        }
+    }
+    ([a[0][0], a[1][1], a[2][2]], v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OFFSET: [f32; 3] = [12.0, -30.0, 7.0];
+
+    /// Unit vectors spread evenly over the sphere.
+    fn sphere(n: usize) -> impl Iterator<Item = [f32; 3]> {
+        let golden = core::f32::consts::PI * (3.0 - libm::sqrtf(5.0));
+        (0..n).map(move |i| {
+            let z = 1.0 - 2.0 * (i as f32 + 0.5) / n as f32;
+            let r = libm::sqrtf(1.0 - z * z);
+            let (s, c) = libm::sincosf(golden * i as f32);
+            [r * c, r * s, z]
+        })
+    }
+
+    /// Scale each axis, then rotate 30 degrees about z, and shift by `OFFSET`.
+    fn distort(v: [f32; 3]) -> MagneticField {
+        let scaled = [v[0] * 60.0, v[1] * 45.0, v[2] * 50.0];
+        let (s, c) = libm::sincosf(30f32.to_radians());
+        MagneticField {
+            x: c * scaled[0] - s * scaled[1] + OFFSET[0],
+            y: s * scaled[0] + c * scaled[1] + OFFSET[1],
+            z: scaled[2] + OFFSET[2],
+        }
+    }
+
+    #[test]
+    fn fit_recovers_ellipsoid() {
+        let mut calibrator = MagCalibrator::new();
+        for v in sphere(200) {
+            calibrator.add(distort(v));
+        }
+        assert_eq!(calibrator.len(), 200);
+
+        let calibration = calibrator.fit().unwrap();
+        for (fitted, expected) in calibration.offset.iter().zip(OFFSET.iter()) {
+            assert!((fitted - expected).abs() < 0.01, "{:?}", calibration.offset);
+        }
+        // volume-preserving radius
+        assert!((calibration.field - libm::cbrtf(60.0 * 45.0 * 50.0)).abs() < 0.01);
+        assert!(calibration.fit_error < 1e-4);
+
+        for v in sphere(50) {
+            let corrected = calibration.apply(distort(v));
+            assert!((corrected.magnitude() - calibration.field).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn single_plane_rotation_is_rejected() {
+        let mut calibrator = MagCalibrator::new();
+        for i in 0..100 {
+            let (s, c) = libm::sincosf(i as f32 * 0.1);
+            calibrator.add(distort([c * 0.8, s * 0.8, 0.6]));
+        }
+        assert!(calibrator.fit().is_none());
+    }
+
+    #[test]
+    fn too_few_samples() {
+        let mut calibrator = MagCalibrator::new();
+        for v in sphere(MIN_CALIBRATION_SAMPLES as usize - 1) {
+            calibrator.add(distort(v));
+        }
+        assert!(calibrator.fit().is_none());
+    }
+}
@@ -0,0 +1,13 @@
+/// Magnetic field strength in microtesla.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct MagneticField {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl MagneticField {
+    pub fn magnitude(&self) -> f32 {
+        libm::sqrt((self.x * self.x + self.y * self.y + self.z * self.z) as f64) as f32
+    }
+}
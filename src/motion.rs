@@ -0,0 +1,26 @@
+/// Axes and polarity of the last detected motion, from MOT_DETECT_STATUS.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct MotionDetectStatus {
+    pub x_neg: bool,
+    pub x_pos: bool,
+    pub y_neg: bool,
+    pub y_pos: bool,
+    pub z_neg: bool,
+    pub z_pos: bool,
+    /// Zero-motion detected.
+    pub zero_motion: bool,
+}
+
+impl MotionDetectStatus {
+    pub(crate) fn from_byte(byte: u8) -> Self {
+        Self {
+            x_neg:       (byte & 0b10000000) != 0,
+            x_pos:       (byte & 0b01000000) != 0,
+            y_neg:       (byte & 0b00100000) != 0,
+            y_pos:       (byte & 0b00010000) != 0,
+            z_neg:       (byte & 0b00001000) != 0,
+            z_pos:       (byte & 0b00000100) != 0,
+            zero_motion: (byte & 0b00000001) != 0,
+        }
+    }
+}
@@ -0,0 +1,19 @@
+/// Screen orientation reported by the DMP's display-orientation feature.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Orientation {
+    Portrait = 0,
+    Landscape = 1,
+    ReversePortrait = 2,
+    ReverseLandscape = 3,
+}
+
+impl Orientation {
+    pub(crate) fn from_byte(byte: u8) -> Self {
+        match (byte & 0xC0) >> 6 {
+            0 => Orientation::Portrait,
+            1 => Orientation::Landscape,
+            2 => Orientation::ReversePortrait,
+            _ => Orientation::ReverseLandscape,
+        }
+    }
+}
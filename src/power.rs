@@ -0,0 +1,69 @@
+/// Frequency at which the accelerometer wakes up in cycle mode.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WakeRate {
+    Hz1_25 = 0,
+    Hz5 = 1,
+    Hz20 = 2,
+    Hz40 = 3,
+}
+
+impl WakeRate {
+    pub(crate) fn from_byte(byte: u8) -> Self {
+        match byte & 0b11 {
+            0 => WakeRate::Hz1_25,
+            1 => WakeRate::Hz5,
+            2 => WakeRate::Hz20,
+            _ => WakeRate::Hz40,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PowerMode {
+    /// All sensors running.
+    Normal,
+    /// All sensors stopped, with register contents retained.
+    Sleep,
+    /// Gyros and temperature sensor off, with the accelerometer waking
+    /// periodically to take a single sample.
+    AccelCycle(WakeRate),
+}
+
+/// Sensor axes placed in standby through PWR_MGMT_2.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct StandbyAxes {
+    pub accel_x: bool,
+    pub accel_y: bool,
+    pub accel_z: bool,
+    pub gyro_x: bool,
+    pub gyro_y: bool,
+    pub gyro_z: bool,
+}
+
+impl StandbyAxes {
+    pub fn none() -> Self {
+        StandbyAxes::default()
+    }
+
+    pub(crate) fn from_byte(byte: u8) -> Self {
+        Self {
+            accel_x: (byte & 0b00100000) != 0,
+            accel_y: (byte & 0b00010000) != 0,
+            accel_z: (byte & 0b00001000) != 0,
+            gyro_x:  (byte & 0b00000100) != 0,
+            gyro_y:  (byte & 0b00000010) != 0,
+            gyro_z:  (byte & 0b00000001) != 0,
+        }
+    }
+
+    pub(crate) fn to_byte(self) -> u8 {
+        let mut byte = 0;
+        if self.accel_x { byte |= 1 << 5 }
+        if self.accel_y { byte |= 1 << 4 }
+        if self.accel_z { byte |= 1 << 3 }
+        if self.gyro_x  { byte |= 1 << 2 }
+        if self.gyro_y  { byte |= 1 << 1 }
+        if self.gyro_z  { byte |= 1 << 0 }
+        byte
+    }
+}
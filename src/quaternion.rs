@@ -1,12 +1,36 @@
+use core::ops::Mul;
+
+/// Fixed-point format of the quaternion components pushed by the DMP.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum QFormat {
+    /// 14 fractional bits in the high 16-bit word of each component (1.0 == 16384 << 16).
+    Q14,
+    /// 30 fractional bits (1.0 == 2^30), as emitted by the bundled firmware.
+    Q30,
+}
+
+impl QFormat {
+    fn to_f32(self, value: i32) -> f32 {
+        match self {
+            QFormat::Q14 => (value >> 16) as f32 / 16384.0,
+            QFormat::Q30 => value as f32 / 1073741824.0,
+        }
+    }
+}
+
+/// Quaternion holding the original fixed-point components from the FIFO.
+///
+/// Useful on targets without an FPU, where the components can be used as-is.
 #[derive(Debug, Copy, Clone)]
-pub struct Quaternion {
-    pub w: f32,
-    pub x: f32,
-    pub y: f32,
-    pub z: f32,
+pub struct QuaternionRaw {
+    pub w: i32,
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
 }
 
-impl Quaternion {
+impl QuaternionRaw {
+    #[allow(clippy::result_unit_err)]
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, ()> {
         if bytes.len() != 16 {
             return Err(());
@@ -38,20 +62,80 @@ impl Quaternion {
             bytes[13],
             bytes[14],
             bytes[15],
-        ]); // as f32 / 16384.0;
+        ]);
 
-        //log::info!("---> {} {} {} {}", w, x, y, z);
+        Ok(Self { w, x, y, z })
+    }
+
+    /// Convert to floating-point, interpreting the components in `format`.
+    pub fn to_quaternion(self, format: QFormat) -> Quaternion {
+        Quaternion {
+            w: format.to_f32(self.w),
+            x: format.to_f32(self.x),
+            y: format.to_f32(self.y),
+            z: format.to_f32(self.z),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Quaternion {
+    pub w: f32,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Quaternion {
+    /// The quaternion representing no rotation.
+    pub const fn identity() -> Self {
+        Self {
+            w: 1.0,
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        }
+    }
+
+    /// Rotation of `angle` radians about `axis`, which need not be normalized.
+    pub fn from_axis_angle(axis: [f32; 3], angle: f32) -> Self {
+        let norm = libm::sqrtf(axis[0] * axis[0] + axis[1] * axis[1] + axis[2] * axis[2]);
+        if norm == 0.0 {
+            return Self::identity();
+        }
+        let (s, c) = libm::sincosf(angle / 2.0);
+        let s = s / norm;
+        Self {
+            w: c,
+            x: axis[0] * s,
+            y: axis[1] * s,
+            z: axis[2] * s,
+        }
+    }
 
+    /// Parse a quaternion, dividing each full 32-bit component by 16384.
+    ///
+    /// Kept for compatibility: this matches neither of the `QFormat` layouts,
+    /// prefer `from_bytes_with_format()`.
+    #[allow(clippy::result_unit_err)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ()> {
+        let raw = QuaternionRaw::from_bytes(bytes)?;
         Ok(Self {
-            w: w as f32 / 16384.0,
-            x: x as f32 / 16384.0,
-            y: y as f32 / 16384.0,
-            z: z as f32 / 16384.0,
+            w: raw.w as f32 / 16384.0,
+            x: raw.x as f32 / 16384.0,
+            y: raw.y as f32 / 16384.0,
+            z: raw.z as f32 / 16384.0,
         })
     }
 
+    /// Parse a quaternion whose components are in the given fixed-point format.
+    #[allow(clippy::result_unit_err)]
+    pub fn from_bytes_with_format(bytes: &[u8], format: QFormat) -> Result<Self, ()> {
+        Ok(QuaternionRaw::from_bytes(bytes)?.to_quaternion(format))
+    }
+
     pub fn magnitude(&self) -> f32 {
-        return libm::sqrt((self.w * self.w + self.x * self.x + self.y * self.y + self.z * self.z) as f64) as f32;
+        libm::sqrt((self.w * self.w + self.x * self.x + self.y * self.y + self.z * self.z) as f64) as f32
     }
 
     pub fn normalize(&self) -> Self {
@@ -63,4 +147,107 @@ impl Quaternion {
             z: self.z / m,
         }
     }
-}
\ No newline at end of file
+
+    pub fn conjugate(&self) -> Self {
+        Self {
+            w: self.w,
+            x: -self.x,
+            y: -self.y,
+            z: -self.z,
+        }
+    }
+
+    /// Multiplicative inverse; equal to the conjugate for unit quaternions.
+    pub fn inverse(&self) -> Self {
+        let n = self.dot(self);
+        Self {
+            w: self.w / n,
+            x: -self.x / n,
+            y: -self.y / n,
+            z: -self.z / n,
+        }
+    }
+
+    pub fn dot(&self, other: &Self) -> f32 {
+        self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// Rotate a vector by this unit quaternion, e.g. from the sensor axes into the world frame.
+    pub fn rotate_vector(&self, v: [f32; 3]) -> [f32; 3] {
+        // v + 2w(u x v) + 2u x (u x v), with u the vector part
+        let u = [self.x, self.y, self.z];
+        let t = cross(u, v);
+        let t = [2.0 * t[0], 2.0 * t[1], 2.0 * t[2]];
+        let ut = cross(u, t);
+        [
+            v[0] + self.w * t[0] + ut[0],
+            v[1] + self.w * t[1] + ut[1],
+            v[2] + self.w * t[2] + ut[2],
+        ]
+    }
+}
+
+/// Hamilton product: `a * b` applies `b`, then `a`.
+impl Mul for Quaternion {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self {
+            w: self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+            x: self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+            y: self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+            z: self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+        }
+    }
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::f32::consts::FRAC_PI_2;
+
+    fn assert_close(a: [f32; 3], b: [f32; 3]) {
+        for (a, b) in a.iter().zip(b.iter()) {
+            assert!((a - b).abs() < 1e-5, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn product_with_inverse_is_identity() {
+        let q = Quaternion { w: 0.5, x: -1.0, y: 2.0, z: 0.25 };
+        let r = q * q.inverse();
+        let identity = Quaternion::identity();
+        assert!((r.w - identity.w).abs() < 1e-6);
+        assert_close([r.x, r.y, r.z], [identity.x, identity.y, identity.z]);
+    }
+
+    #[test]
+    fn rotate_x_about_z() {
+        let q = Quaternion::from_axis_angle([0.0, 0.0, 2.0], FRAC_PI_2);
+        assert_close(q.rotate_vector([1.0, 0.0, 0.0]), [0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn rotate_vector_matches_sandwich_product() {
+        let q = Quaternion::from_axis_angle([1.0, -2.0, 0.5], 1.3);
+        let v = [0.3, -1.2, 2.0];
+        let p = q * Quaternion { w: 0.0, x: v[0], y: v[1], z: v[2] } * q.conjugate();
+        assert!(p.w.abs() < 1e-5);
+        assert_close(q.rotate_vector(v), [p.x, p.y, p.z]);
+    }
+
+    #[test]
+    fn q14_uses_high_word() {
+        let raw = QuaternionRaw { w: 16384 << 16, x: -(8192 << 16), y: 0, z: 0 };
+        let q = raw.to_quaternion(QFormat::Q14);
+        assert_eq!((q.w, q.x), (1.0, -0.5));
+    }
+}
@@ -4,8 +4,13 @@
 pub enum Register {
     Config = 0x1A,
     PwrMgmt1 = 0x6B,
+    PwrMgmt2 = 0x6C,
     SmpRtDiv = 0x19,
 
+    MotThr = 0x1F,
+    MotDur = 0x20,
+    MotDetectStatus = 0x61,
+
     AccelX_H = 0x3B,
     AccelX_L = 0x3C,
     AccelY_H = 0x3D,
@@ -25,9 +30,38 @@ pub enum Register {
     GyroConfig = 0x1B,
 
     UserCtrl = 0x6A,
+    I2cMstStatus = 0x36,
+    IntPinCfg = 0x37,
     IntEnable = 0x38,
+    IntStatus = 0x3A,
 
     FifoEn = 0x23,
+
+    I2cMstCtrl = 0x24,
+    I2cSlv0Addr = 0x25,
+    I2cSlv0Reg = 0x26,
+    I2cSlv0Ctrl = 0x27,
+    I2cSlv1Addr = 0x28,
+    I2cSlv1Reg = 0x29,
+    I2cSlv1Ctrl = 0x2A,
+    I2cSlv2Addr = 0x2B,
+    I2cSlv2Reg = 0x2C,
+    I2cSlv2Ctrl = 0x2D,
+    I2cSlv3Addr = 0x2E,
+    I2cSlv3Reg = 0x2F,
+    I2cSlv3Ctrl = 0x30,
+    I2cSlv4Addr = 0x31,
+    I2cSlv4Reg = 0x32,
+    I2cSlv4Do = 0x33,
+    I2cSlv4Ctrl = 0x34,
+    I2cSlv4Di = 0x35,
+    ExtSensData00 = 0x49,
+    I2cSlv0Do = 0x63,
+    I2cSlv1Do = 0x64,
+    I2cSlv2Do = 0x65,
+    I2cSlv3Do = 0x66,
+    I2cMstDelayCtrl = 0x67,
+
     FifoCount_H = 0x72,
     FifoCount_L = 0x73,
     FifoRw = 0x74,
@@ -2,14 +2,46 @@ use embedded_hal::blocking::i2c::{WriteRead, Write};
 use crate::address::Address;
 use crate::error::Error;
 use crate::registers::Register;
-use crate::accel::{Accel, AccelFullScale};
-use crate::gyro::{Gyro, GyroFullScale};
-use crate::fifo::Fifo;
+use crate::accel::{Accel, AccelFullScale, AccelHighPass};
+use crate::gyro::{Gyro, GyroBiases, GyroFullScale};
+use crate::fifo::{Fifo, FifoFrameLayout};
 use drogue_embedded_timer::Delay;
 use embedded_time::duration::Milliseconds;
 use crate::clock_source::ClockSource;
-use crate::config::DigitalLowPassFilter;
-use core::marker::PhantomData;
+use crate::config::{DigitalLowPassFilter, FrameSyncSource, FsyncLevel};
+use crate::power::{PowerMode, StandbyAxes, WakeRate};
+use crate::motion::MotionDetectStatus;
+use crate::aux::{AuxSlave, MST_CTRL_SLV_3_FIFO_EN};
+use crate::firmware_version::FirmwareVersion;
+use crate::dmp_packet::{DmpOutputs, DmpPacketLayout};
+
+const PWR1_SLEEP: u8 = 1 << 6;
+const PWR1_CYCLE: u8 = 1 << 5;
+const PWR1_TEMP_DIS: u8 = 1 << 3;
+const PWR2_STBY_ACCEL: u8 = 0b0011_1000;
+const PWR2_STBY_GYRO: u8 = 0b0000_0111;
+const INT_MOT: u8 = 1 << 6;
+const USER_CTRL_DMP_EN: u8 = 1 << 7;
+const CONFIG_DLPF_MASK: u8 = 0b0000_0111;
+const CONFIG_EXT_SYNC_MASK: u8 = 0b0011_1000;
+const INT_PIN_FSYNC_LEVEL: u8 = 1 << 3;
+const INT_PIN_FSYNC_EN: u8 = 1 << 2;
+const I2C_MST_STATUS_FSYNC: u8 = 1 << 7;
+const ACCEL_FS_MASK: u8 = 0b0001_1000;
+const ACCEL_HPF_MASK: u8 = 0b0000_0111;
+
+/// Gyro start-up time from sleep, per the datasheet.
+const GYRO_STARTUP_MS: u32 = 30;
+
+/// Registers overwritten by `enter_wake_on_motion()`, besides power management.
+struct WakeOnMotionSaved {
+    config: u8,
+    accel_config: u8,
+    int_enable: u8,
+    mot_thr: u8,
+    mot_dur: u8,
+    user_ctrl: u8,
+}
 
 /// InvenSense MPU-6050 Driver
 pub struct Mpu6050<'clock, I2c, Clock>
@@ -19,9 +51,15 @@ pub struct Mpu6050<'clock, I2c, Clock>
           Clock: embedded_time::Clock,
 
 {
-    i2c: I2c,
+    pub(crate) i2c: I2c,
     address: u8,
-    clock: &'clock Clock,
+    pub(crate) clock: &'clock Clock,
+    pub(crate) dmp_layout: DmpPacketLayout,
+    pub(crate) firmware: Option<FirmwareVersion>,
+    saved_power: Option<[u8; 2]>,
+    standby: StandbyAxes,
+    sleeping: bool,
+    saved_wake_on_motion: Option<WakeOnMotionSaved>,
 }
 
 impl<'clock, I2c, Clock> Mpu6050<'clock, I2c, Clock>
@@ -37,16 +75,32 @@ impl<'clock, I2c, Clock> Mpu6050<'clock, I2c, Clock>
             i2c,
             address: address.into(),
             clock,
+            dmp_layout: DmpPacketLayout::default(),
+            firmware: None,
+            saved_power: None,
+            standby: StandbyAxes::none(),
+            sleeping: true,
+            saved_wake_on_motion: None,
         };
 
         sensor.disable_sleep()?;
+        sensor.standby = StandbyAxes::from_byte(sensor.read_register(Register::PwrMgmt2)?);
 
         Ok(sensor)
     }
 
     /// Load DMP firmware and perform all appropriate initialization.
+    ///
+    /// If the bundled firmware is already resident (for instance after a
+    /// warm reset of the host), the device reset and firmware upload are skipped;
+    /// the configuration registers are returned to their power-on values instead.
     pub fn initialize_dmp(&mut self) -> Result<(), Error<I2c>> {
-        self.reset()?;
+        let loaded = self.firmware_version()? == FirmwareVersion::MotionDriver612;
+        if loaded {
+            self.restore_register_defaults()?;
+        } else {
+            self.reset()?;
+        }
         self.disable_sleep()?;
         self.reset_signal_path()?;
         self.disable_dmp()?;
@@ -56,9 +110,16 @@ impl<'clock, I2c, Clock> Mpu6050<'clock, I2c, Clock>
         self.set_accel_full_scale(AccelFullScale::G2)?;
         self.set_sample_rate_divider(4)?;
         self.set_digital_lowpass_filter(DigitalLowPassFilter::Filter1)?;
-        self.load_firmware()?;
+        if !loaded {
+            self.load_firmware()?;
+        }
         self.boot_firmware()?;
         self.set_gyro_full_scale(GyroFullScale::Deg2000)?;
+        self.set_dmp_outputs(DmpOutputs::default())?;
+        self.disable_dmp_tap()?;
+        self.disable_dmp_orientation()?;
+        self.enable_dmp_gyro_calibration(true)?;
+        self.set_dmp_gyro_bias(GyroBiases::default())?;
         self.enable_fifo()?;
         self.reset_fifo()?;
         self.disable_dmp()?;
@@ -81,7 +142,7 @@ impl<'clock, I2c, Clock> Mpu6050<'clock, I2c, Clock>
             .map_err(|e| Error::WriteError(e))
     }
 
-    pub(crate) fn read_register<'a>(&mut self, reg: Register) -> Result<u8, Error<I2c>> {
+    pub(crate) fn read_register(&mut self, reg: Register) -> Result<u8, Error<I2c>> {
         let mut buf = [0; 1];
         self.read(
             &[reg as u8],
@@ -106,16 +167,57 @@ impl<'clock, I2c, Clock> Mpu6050<'clock, I2c, Clock>
     /// Perform power reset of the MPU
     pub fn reset(&mut self) -> Result<(), Error<I2c>> {
         let mut value = self.read_register(Register::PwrMgmt1)?;
-        value |= (1 << 7);
+        value |= 1 << 7;
         self.write_register(Register::PwrMgmt1, value)?;
+        self.firmware = None;
+        self.saved_power = None;
+        self.standby = StandbyAxes::none();
+        // the device comes out of reset in sleep mode
+        self.sleeping = true;
+        self.saved_wake_on_motion = None;
         Delay::new(self.clock).delay(Milliseconds(200));
         Ok(())
     }
 
+    /// Return the configuration registers to their power-on values, leaving DMP memory intact.
+    ///
+    /// Used instead of `reset()` when the firmware is already resident, so that
+    /// nothing configured by a previous run (cycle mode, standby axes, the
+    /// auxiliary master, ...) survives into the DMP setup.
+    fn restore_register_defaults(&mut self) -> Result<(), Error<I2c>> {
+        self.write_pwr_mgmt1(PWR1_SLEEP)?;
+        self.write_pwr_mgmt2(0)?;
+        self.saved_power = None;
+        self.saved_wake_on_motion = None;
+
+        for reg in [
+            Register::UserCtrl,
+            Register::IntPinCfg,
+            Register::IntEnable,
+            Register::FifoEn,
+            Register::SmpRtDiv,
+            Register::Config,
+            Register::GyroConfig,
+            Register::AccelConfig,
+            Register::MotThr,
+            Register::MotDur,
+            Register::I2cMstCtrl,
+            Register::I2cSlv0Ctrl,
+            Register::I2cSlv1Ctrl,
+            Register::I2cSlv2Ctrl,
+            Register::I2cSlv3Ctrl,
+            Register::I2cSlv4Ctrl,
+            Register::I2cMstDelayCtrl,
+        ] {
+            self.write_register(reg, 0)?;
+        }
+        Ok(())
+    }
+
     /// Perform reset of the signal path
     pub fn reset_signal_path(&mut self) -> Result<(), Error<I2c>> {
         let mut value = self.read_register(Register::UserCtrl)?;
-        value |= (1 << 0);
+        value |= 1 << 0;
         self.write_register(Register::UserCtrl, value)?;
         Delay::new(self.clock).delay(Milliseconds(200));
         Ok(())
@@ -125,27 +227,138 @@ impl<'clock, I2c, Clock> Mpu6050<'clock, I2c, Clock>
     pub fn set_clock_source(&mut self, clock_source: ClockSource) -> Result<(), Error<I2c>> {
         let mut value = self.read_register(Register::PwrMgmt1)?;
         value |= clock_source as u8;
-        self.write_register(Register::PwrMgmt1, value);
-        Ok(())
+        self.write_register(Register::PwrMgmt1, value)
     }
 
     pub fn disable_interrupts(&mut self) -> Result<(), Error<I2c>> {
         self.write_register(Register::IntEnable, 0x00)
     }
 
-    pub fn calibrate_accel(&mut self, loops: u8) -> Result<(), Error<I2c>> {
+    /// Raise the motion interrupt when acceleration exceeds `threshold_mg`
+    /// for at least `duration_ms`.
+    ///
+    /// The threshold has a resolution of 2 mg, up to 510 mg.
+    /// Motion detection also requires the accel high-pass filter, see `set_accel_high_pass()`.
+    pub fn configure_motion_detection(&mut self, threshold_mg: u16, duration_ms: u8) -> Result<(), Error<I2c>> {
+        let threshold = (threshold_mg / 2).min(0xFF) as u8;
+        self.write_register(Register::MotThr, threshold)?;
+        self.write_register(Register::MotDur, duration_ms)?;
+        let mut value = self.read_register(Register::IntEnable)?;
+        value |= INT_MOT;
+        self.write_register(Register::IntEnable, value)
+    }
+
+    pub fn disable_motion_detection(&mut self) -> Result<(), Error<I2c>> {
+        let mut value = self.read_register(Register::IntEnable)?;
+        value &= !INT_MOT;
+        self.write_register(Register::IntEnable, value)
+    }
+
+    /// Whether motion was detected since the last call.
+    ///
+    /// Reading INT_STATUS clears all pending interrupts.
+    pub fn motion_detected(&mut self) -> Result<bool, Error<I2c>> {
+        let value = self.read_register(Register::IntStatus)?;
+        Ok((value & INT_MOT) != 0)
+    }
+
+    /// Axes and polarity of the last detected motion.
+    pub fn motion_detect_status(&mut self) -> Result<MotionDetectStatus, Error<I2c>> {
+        let value = self.read_register(Register::MotDetectStatus)?;
+        Ok(MotionDetectStatus::from_byte(value))
+    }
+
+    /// Enter the lowest-power state which still raises the motion interrupt.
+    ///
+    /// The DMP is stopped, the gyros placed in standby and the accelerometer
+    /// cycled at `rate`, with the high-pass filter holding the current sample
+    /// as the reference for `threshold_mg`. Use `exit_wake_on_motion()` to
+    /// restore the previous configuration.
+    pub fn enter_wake_on_motion(&mut self, threshold_mg: u16, rate: WakeRate) -> Result<(), Error<I2c>> {
+        if self.saved_wake_on_motion.is_none() {
+            self.saved_wake_on_motion = Some(WakeOnMotionSaved {
+                config: self.read_register(Register::Config)?,
+                accel_config: self.read_register(Register::AccelConfig)?,
+                int_enable: self.read_register(Register::IntEnable)?,
+                mot_thr: self.read_register(Register::MotThr)?,
+                mot_dur: self.read_register(Register::MotDur)?,
+                user_ctrl: self.read_register(Register::UserCtrl)?,
+            });
+        }
+        self.save_power_config()?;
+
+        self.disable_interrupts()?;
+        self.disable_dmp()?;
+
+        // full-power accel-only mode, with the low-pass filter bypassed
+        self.write_pwr_mgmt1(ClockSource::Internal as u8)?;
+        self.write_pwr_mgmt2(PWR2_STBY_GYRO)?;
+        self.write_register(Register::Config, 0)?;
+        self.set_accel_high_pass(AccelHighPass::Reset)?;
+
+        self.configure_motion_detection(threshold_mg, 1)?;
+
+        // let the filter settle, then hold the current sample as the reference
+        Delay::new(self.clock).delay(Milliseconds(5u32));
+        self.set_accel_high_pass(AccelHighPass::Hold)?;
+
+        self.set_power_mode(PowerMode::AccelCycle(rate))
+    }
+
+    /// Leave wake-on-motion mode, restoring the previous configuration
+    /// and resuming the DMP if it was running.
+    pub fn exit_wake_on_motion(&mut self) -> Result<(), Error<I2c>> {
+        let saved = match self.saved_wake_on_motion.take() {
+            Some(saved) => saved,
+            None => return Ok(()),
+        };
+
+        self.wake()?;
+        self.write_register(Register::Config, saved.config)?;
+        self.write_register(Register::AccelConfig, saved.accel_config)?;
+        self.write_register(Register::MotThr, saved.mot_thr)?;
+        self.write_register(Register::MotDur, saved.mot_dur)?;
+        self.write_register(Register::IntEnable, saved.int_enable)?;
+
+        if (saved.user_ctrl & USER_CTRL_DMP_EN) != 0 {
+            self.reset_fifo()?;
+            self.enable_dmp()?;
+        }
+        Ok(())
+    }
+
+    pub fn calibrate_accel(&mut self, _loops: u8) -> Result<(), Error<I2c>> {
         Ok(())
     }
 
     pub fn set_accel_full_scale(&mut self, scale: AccelFullScale) -> Result<(), Error<I2c>> {
         let mut value = self.read_register(Register::AccelConfig)?;
-        value |= ((scale as u8) << 3);
+        value &= !ACCEL_FS_MASK;
+        value |= (scale as u8) << 3;
+        self.write_register(Register::AccelConfig, value)
+    }
+
+    pub fn accel_full_scale(&mut self) -> Result<AccelFullScale, Error<I2c>> {
+        let value = self.read_register(Register::AccelConfig)?;
+        Ok(AccelFullScale::from_byte(value >> 3))
+    }
+
+    /// Set the accel high-pass filter, preserving the full-scale range.
+    pub fn set_accel_high_pass(&mut self, filter: AccelHighPass) -> Result<(), Error<I2c>> {
+        let mut value = self.read_register(Register::AccelConfig)?;
+        value &= !ACCEL_HPF_MASK;
+        value |= filter as u8;
         self.write_register(Register::AccelConfig, value)
     }
 
+    pub fn accel_high_pass(&mut self) -> Result<AccelHighPass, Error<I2c>> {
+        let value = self.read_register(Register::AccelConfig)?;
+        Ok(AccelHighPass::from_byte(value))
+    }
+
     pub fn set_gyro_full_scale(&mut self, scale: GyroFullScale) -> Result<(), Error<I2c>> {
         let mut value = self.read_register(Register::GyroConfig)?;
-        value |= ((scale as u8) << 3);
+        value |= (scale as u8) << 3;
         self.write_register(Register::GyroConfig, value)
     }
 
@@ -155,19 +368,57 @@ impl<'clock, I2c, Clock> Mpu6050<'clock, I2c, Clock>
 
     pub fn set_digital_lowpass_filter(&mut self, filter: DigitalLowPassFilter) -> Result<(), Error<I2c>> {
         let mut value = self.read_register(Register::Config)?;
-        value |= (filter as u8);
+        value &= !CONFIG_DLPF_MASK;
+        value |= filter as u8;
+        self.write_register(Register::Config, value)
+    }
+
+    /// Latch the FSYNC pin state into the LSB of the given sensor output,
+    /// to align samples with an external frame trigger.
+    pub fn set_frame_sync(&mut self, source: FrameSyncSource) -> Result<(), Error<I2c>> {
+        let mut value = self.read_register(Register::Config)?;
+        value &= !CONFIG_EXT_SYNC_MASK;
+        value |= (source as u8) << 3;
         self.write_register(Register::Config, value)
     }
 
+    pub fn frame_sync(&mut self) -> Result<FrameSyncSource, Error<I2c>> {
+        let value = self.read_register(Register::Config)?;
+        Ok(FrameSyncSource::from_byte(value >> 3))
+    }
+
+    /// Use the FSYNC pin as an interrupt to the host, triggered at `level`.
+    pub fn enable_fsync_interrupt(&mut self, level: FsyncLevel) -> Result<(), Error<I2c>> {
+        let mut value = self.read_register(Register::IntPinCfg)?;
+        match level {
+            FsyncLevel::ActiveHigh => value &= !INT_PIN_FSYNC_LEVEL,
+            FsyncLevel::ActiveLow => value |= INT_PIN_FSYNC_LEVEL,
+        }
+        value |= INT_PIN_FSYNC_EN;
+        self.write_register(Register::IntPinCfg, value)
+    }
+
+    pub fn disable_fsync_interrupt(&mut self) -> Result<(), Error<I2c>> {
+        let mut value = self.read_register(Register::IntPinCfg)?;
+        value &= !INT_PIN_FSYNC_EN;
+        self.write_register(Register::IntPinCfg, value)
+    }
+
+    /// Whether an FSYNC interrupt occurred since the last call.
+    pub fn fsync_interrupt(&mut self) -> Result<bool, Error<I2c>> {
+        let value = self.read_register(Register::I2cMstStatus)?;
+        Ok((value & I2C_MST_STATUS_FSYNC) != 0)
+    }
+
     pub fn reset_fifo(&mut self) -> Result<(), Error<I2c>> {
         let mut value = self.read_register(Register::UserCtrl)?;
-        value |= (1 << 2);
+        value |= 1 << 2;
         self.write_register(Register::UserCtrl, value)
     }
 
     pub fn enable_fifo(&mut self) -> Result<(), Error<I2c>> {
         let mut value = self.read_register(Register::UserCtrl)?;
-        value |= (1 << 6);
+        value |= 1 << 6;
         self.write_register(Register::UserCtrl, value)
     }
 
@@ -175,7 +426,7 @@ impl<'clock, I2c, Clock> Mpu6050<'clock, I2c, Clock>
     /// To perform full DMP initialization, see `initialize_dmp()`
     pub fn enable_dmp(&mut self) -> Result<(), Error<I2c>> {
         let mut value = self.read_register(Register::UserCtrl)?;
-        value |= (1 << 7);
+        value |= 1 << 7;
         self.write_register(Register::UserCtrl, value)
     }
 
@@ -189,10 +440,15 @@ impl<'clock, I2c, Clock> Mpu6050<'clock, I2c, Clock>
     /// Reset the DMP processor
     pub fn reset_dmp(&mut self) -> Result<(), Error<I2c>> {
         let mut value = self.read_register(Register::UserCtrl)?;
-        value |= (1 << 3);
+        value |= 1 << 3;
         self.write_register(Register::UserCtrl, value)
     }
 
+    /// Layout of the packets the DMP currently pushes into the FIFO.
+    pub fn dmp_packet_layout(&self) -> DmpPacketLayout {
+        self.dmp_layout
+    }
+
     /// Read the FIFO
     pub fn read_fifo<'a>(&mut self, buf: &'a mut [u8]) -> Result<&'a [u8], Error<I2c>> {
         let mut len = self.get_fifo_count()?;
@@ -217,27 +473,179 @@ impl<'clock, I2c, Clock> Mpu6050<'clock, I2c, Clock>
         self.write_register(Register::FifoEn, fifo.to_byte())
     }
 
+    /// Layout of the raw sensor frames for the currently enabled FIFO sources,
+    /// including the data read by auxiliary slaves 0 to 3.
+    pub fn fifo_frame_layout(&mut self) -> Result<FifoFrameLayout, Error<I2c>> {
+        let fifo = self.get_fifo_enabled()?;
+        let slave3 = (self.read_register(Register::I2cMstCtrl)? & MST_CTRL_SLV_3_FIFO_EN) != 0;
+        let mut layout = FifoFrameLayout::from(fifo).with_slave3_fifo(slave3);
+        let slaves = [
+            (fifo.slv0, AuxSlave::Slv0),
+            (fifo.slv1, AuxSlave::Slv1),
+            (fifo.slv2, AuxSlave::Slv2),
+            (slave3, AuxSlave::Slv3),
+        ];
+        for (enabled, slave) in slaves.iter() {
+            if *enabled {
+                layout = layout.with_slave_len(*slave, self.aux_slave_len(*slave)?);
+            }
+        }
+        Ok(layout)
+    }
+
+    /// Read as many complete frames from the FIFO as fit in `buf`.
+    /// Parse the result with `FifoFrameLayout::frames()`.
+    pub fn read_fifo_frames<'a>(&mut self, layout: &FifoFrameLayout, buf: &'a mut [u8]) -> Result<&'a [u8], Error<I2c>> {
+        let frame = layout.len();
+        if frame == 0 {
+            return Ok(&buf[0..0]);
+        }
+        let available = self.get_fifo_count()?.min(buf.len());
+        let len = available - available % frame;
+        if len == 0 {
+            Ok(&buf[0..0])
+        } else {
+            self.read_registers(Register::FifoRw, &mut buf[0..len])
+        }
+    }
+
     pub fn get_fifo_count(&mut self) -> Result<usize, Error<I2c>> {
         let mut buf = [0; 2];
-        let mut value = self.read_registers(Register::FifoCount_H, &mut buf)?;
+        self.read_registers(Register::FifoCount_H, &mut buf)?;
         Ok(u16::from_be_bytes(buf) as usize)
     }
 
     pub fn disable_sleep(&mut self) -> Result<(), Error<I2c>> {
         let mut value = self.read_register(Register::PwrMgmt1)?;
         value &= !(1 << 6);
-        self.write_register(Register::PwrMgmt1, value)
+        self.write_pwr_mgmt1(value)
+    }
+
+    /// Switch power mode.
+    ///
+    /// Entering `Sleep` or `AccelCycle` saves the power management configuration,
+    /// which is restored when switching back to `Normal`.
+    pub fn set_power_mode(&mut self, mode: PowerMode) -> Result<(), Error<I2c>> {
+        match mode {
+            PowerMode::Normal => {
+                if let Some([pwr1, pwr2]) = self.saved_power.take() {
+                    self.write_pwr_mgmt2(pwr2)?;
+                    self.write_pwr_mgmt1(pwr1)
+                } else {
+                    let mut value = self.read_register(Register::PwrMgmt1)?;
+                    value &= !(PWR1_SLEEP | PWR1_CYCLE);
+                    self.write_pwr_mgmt1(value)
+                }
+            }
+            PowerMode::Sleep => {
+                self.save_power_config()?;
+                let mut value = self.read_register(Register::PwrMgmt1)?;
+                value &= !PWR1_CYCLE;
+                value |= PWR1_SLEEP;
+                self.write_pwr_mgmt1(value)
+            }
+            PowerMode::AccelCycle(rate) => {
+                self.save_power_config()?;
+                let mut value = self.read_register(Register::PwrMgmt2)?;
+                value &= PWR2_STBY_ACCEL;
+                value |= ((rate as u8) << 6) | PWR2_STBY_GYRO;
+                self.write_pwr_mgmt2(value)?;
+                // the gyro clock is unavailable with the gyros in standby
+                self.write_pwr_mgmt1(PWR1_CYCLE | PWR1_TEMP_DIS | ClockSource::Internal as u8)
+            }
+        }
+    }
+
+    /// Current power mode.
+    pub fn power_mode(&mut self) -> Result<PowerMode, Error<I2c>> {
+        let pwr1 = self.read_register(Register::PwrMgmt1)?;
+        if pwr1 & PWR1_SLEEP != 0 {
+            Ok(PowerMode::Sleep)
+        } else if pwr1 & PWR1_CYCLE != 0 {
+            let pwr2 = self.read_register(Register::PwrMgmt2)?;
+            Ok(PowerMode::AccelCycle(WakeRate::from_byte(pwr2 >> 6)))
+        } else {
+            Ok(PowerMode::Normal)
+        }
+    }
+
+    /// Place individual accel and gyro axes in standby.
+    /// Readings of axes in standby are flagged by `Accel` and `Gyro`.
+    pub fn set_standby(&mut self, standby: StandbyAxes) -> Result<(), Error<I2c>> {
+        let mut value = self.read_register(Register::PwrMgmt2)?;
+        value &= !(PWR2_STBY_ACCEL | PWR2_STBY_GYRO);
+        value |= standby.to_byte();
+        self.write_pwr_mgmt2(value)
+    }
+
+    /// Axes currently in standby.
+    pub fn standby(&mut self) -> Result<StandbyAxes, Error<I2c>> {
+        let value = self.read_register(Register::PwrMgmt2)?;
+        self.standby = StandbyAxes::from_byte(value);
+        Ok(self.standby)
+    }
+
+    /// Put the MPU to sleep. Sensor reads fail with `Error::Sleeping` until `wake()`.
+    pub fn sleep(&mut self) -> Result<(), Error<I2c>> {
+        self.set_power_mode(PowerMode::Sleep)
+    }
+
+    /// Wake the MPU, restoring the configuration from before `sleep()`.
+    ///
+    /// Waits for the gyros to start up, unless all of them are in standby.
+    pub fn wake(&mut self) -> Result<(), Error<I2c>> {
+        self.set_power_mode(PowerMode::Normal)?;
+        if !(self.standby.gyro_x && self.standby.gyro_y && self.standby.gyro_z) {
+            Delay::new(self.clock).delay(Milliseconds(GYRO_STARTUP_MS));
+        }
+        Ok(())
+    }
+
+    pub fn is_sleeping(&self) -> bool {
+        self.sleeping
+    }
+
+    fn write_pwr_mgmt1(&mut self, value: u8) -> Result<(), Error<I2c>> {
+        self.write_register(Register::PwrMgmt1, value)?;
+        self.sleeping = (value & PWR1_SLEEP) != 0;
+        Ok(())
+    }
+
+    fn write_pwr_mgmt2(&mut self, value: u8) -> Result<(), Error<I2c>> {
+        self.write_register(Register::PwrMgmt2, value)?;
+        self.standby = StandbyAxes::from_byte(value);
+        Ok(())
+    }
+
+    /// Remember the full-power configuration, unless already in a low-power mode.
+    fn save_power_config(&mut self) -> Result<(), Error<I2c>> {
+        if self.saved_power.is_none() {
+            let pwr1 = self.read_register(Register::PwrMgmt1)?;
+            let pwr2 = self.read_register(Register::PwrMgmt2)?;
+            self.saved_power = Some([pwr1, pwr2]);
+        }
+        Ok(())
     }
 
     pub fn accel(&mut self) -> Result<Accel, Error<I2c>> {
+        self.require_awake()?;
         let mut data = [0; 6];
         self.read_registers(Register::AccelX_H, &mut data)?;
-        Ok(Accel::new(data))
+        Ok(Accel::new(data).with_standby(&self.standby))
     }
 
     pub fn gyro(&mut self) -> Result<Gyro, Error<I2c>> {
+        self.require_awake()?;
         let mut data = [0; 6];
         self.read_registers(Register::GyroX_H, &mut data)?;
-        Ok(Gyro::new(data))
+        Ok(Gyro::new(data).with_standby(&self.standby))
+    }
+
+    fn require_awake(&self) -> Result<(), Error<I2c>> {
+        if self.sleeping {
+            Err(Error::Sleeping)
+        } else {
+            Ok(())
+        }
     }
-}
\ No newline at end of file
+}
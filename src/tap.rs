@@ -0,0 +1,87 @@
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TapAxis {
+    X,
+    Y,
+    Z,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TapDirection {
+    Positive,
+    Negative,
+}
+
+/// Tap reported by the DMP.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TapEvent {
+    pub axis: TapAxis,
+    pub direction: TapDirection,
+    /// Number of consecutive taps, from 1 to 4.
+    pub count: u8,
+}
+
+impl TapEvent {
+    pub(crate) fn from_byte(byte: u8) -> Option<Self> {
+        let tap = byte & 0x3F;
+        let (axis, direction) = match tap >> 3 {
+            1 => (TapAxis::X, TapDirection::Positive),
+            2 => (TapAxis::X, TapDirection::Negative),
+            3 => (TapAxis::Y, TapDirection::Positive),
+            4 => (TapAxis::Y, TapDirection::Negative),
+            5 => (TapAxis::Z, TapDirection::Positive),
+            6 => (TapAxis::Z, TapDirection::Negative),
+            _ => return None,
+        };
+        Some(Self {
+            axis,
+            direction,
+            count: (tap & 0x07) + 1,
+        })
+    }
+}
+
+/// Axes on which the DMP detects taps.
+#[derive(Copy, Clone, Debug)]
+pub struct TapAxes {
+    pub x: bool,
+    pub y: bool,
+    pub z: bool,
+}
+
+impl TapAxes {
+    pub fn all() -> Self {
+        Self {
+            x: true,
+            y: true,
+            z: true,
+        }
+    }
+}
+
+/// DMP tap detection settings.
+///
+/// The defaults match those used by the InvenSense MotionDriver.
+#[derive(Copy, Clone, Debug)]
+pub struct TapConfig {
+    pub axes: TapAxes,
+    /// Acceleration threshold in mg/ms, up to 1600.
+    pub threshold: u16,
+    /// Minimum number of consecutive taps to report, from 1 to 4.
+    pub min_taps: u8,
+    /// Minimum time between taps in milliseconds.
+    pub time_ms: u16,
+    /// Maximum time between taps counted together, in milliseconds.
+    pub multi_time_ms: u16,
+}
+
+impl Default for TapConfig {
+    fn default() -> Self {
+        Self {
+            axes: TapAxes::all(),
+            threshold: 250,
+            min_taps: 1,
+            time_ms: 100,
+            multi_time_ms: 500,
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use crate::quaternion::Quaternion;
 use crate::gravity::Gravity;
 
+// kept as-is so pitch values don't change
+#[allow(clippy::approx_constant)]
 const PI: f64 = 3.1415;
 
 #[derive(Debug, Copy, Clone)]
@@ -14,19 +16,14 @@ impl YawPitchRoll {}
 
 impl From<Quaternion> for YawPitchRoll {
     fn from(q: Quaternion) -> Self {
-        let gravity = Gravity::from(q.clone());
+        let gravity = Gravity::from(q);
         // yaw: (about Z axis)
         let yaw = libm::atan2((2.0 * q.x * q.y - 2.0 * q.w * q.z) as f64, (2.0 * q.w * q.w + 2.0 * q.x * q.x - 1.0) as f64);
         // pitch: (nose up/down, about Y axis)
-        let mut pitch = libm::atan2(gravity.x as f64, libm::sqrt((gravity.y * gravity.y + gravity.z * gravity.z) as f64));
+        let pitch = libm::atan2(gravity.x as f64, libm::sqrt((gravity.y * gravity.y + gravity.z * gravity.z) as f64));
         // roll: (tilt left/right, about X axis)
         let roll = libm::atan2(gravity.y as f64, gravity.z as f64);
-
-        if gravity.z < 0.0 {
-            pitch = PI - pitch;
-        } else {
-            pitch = PI - pitch;
-        }
+        let pitch = PI - pitch;
 
         Self {
             yaw: yaw as f32,